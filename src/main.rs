@@ -1,72 +1,192 @@
 mod lexer;
 mod openapi;
 mod parser;
-use std::{
-    fs,
-    io::Error,
-    path::{Path, PathBuf},
-};
+mod rules;
+mod secrets;
+mod wsdl;
+use std::{fs, path::PathBuf};
 
-use oapi::OApi;
-use serde_json::Value;
-use sppparse::SparseRoot;
+use anyhow::Context;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 
 use crate::{
-    lexer::{Lexer, Token},
-    openapi::{OpenApi, PathData, Scanner},
+    openapi::Scanner,
+    rules::headers::HeaderPolicy,
+    wsdl::{
+        imports::ImportResolver, normalise::to_openapi, sample::sample_request,
+        xml::read_document, SoapVersion, WsdlDocument,
+    },
 };
 
-fn run(text: &str) -> anyhow::Result<()> {
-    //in the future, when we add CLI capabilities,
-    //we extract CLI args here...
-    let mut scanner: Scanner = Scanner::new(text);
-    scanner.scan()?;
-    scanner.display()?;
-
-    Ok(())
+fn cli() -> Command {
+    Command::new("rustysec")
+        .about("Scan OpenAPI and SOAP (WSDL) specifications for OWASP API security issues")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("scan")
+                .about("Scan an OpenAPI (JSON) or WSDL specification")
+                .arg(
+                    Arg::new("spec")
+                        .help("Path to the specification file")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("require-header")
+                        .long("require-header")
+                        .value_name("HEADER")
+                        .action(ArgAction::Append)
+                        .help("Response header successful responses must document, repeat for several. Replaces the default set (Strict-Transport-Security, X-Content-Type-Options)"),
+                )
+                .arg(
+                    Arg::new("catalogue")
+                        .long("catalogue")
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Directory holding local copies of documents WSDL imports reference by absolute URL, as host/path or by file name"),
+                ),
+        )
+        .subcommand(
+            Command::new("soap")
+                .about("SOAP (WSDL) tooling")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("sample")
+                        .about("Print sample request envelopes of a WSDL operation, for SOAP 1.1 and 1.2")
+                        .arg(
+                            Arg::new("wsdl")
+                                .help("Path to the WSDL file")
+                                .required(true)
+                                .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("operation")
+                                .help("Name of the operation")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("soap-version")
+                                .long("soap-version")
+                                .value_name("VERSION")
+                                .value_parser(["1.1", "1.2"])
+                                .help("Only print the envelope of this SOAP version"),
+                        )
+                        .arg(
+                            Arg::new("catalogue")
+                                .long("catalogue")
+                                .value_name("DIR")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("Directory holding local copies of documents WSDL imports reference by absolute URL"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert specifications between formats")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("wsdl-to-openapi")
+                        .about("Export the operations and schemas of a WSDL as an OpenAPI 3 (JSON) document")
+                        .arg(
+                            Arg::new("wsdl")
+                                .help("Path to the WSDL file")
+                                .required(true)
+                                .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .short('o')
+                                .value_name("FILE")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("Write the document to this file instead of the standard output"),
+                        )
+                        .arg(
+                            Arg::new("catalogue")
+                                .long("catalogue")
+                                .value_name("DIR")
+                                .value_parser(value_parser!(PathBuf))
+                                .help("Directory holding local copies of documents WSDL imports reference by absolute URL"),
+                        ),
+                ),
+        )
 }
-fn main() {
-    println!("Hello, world!");
-    let open_api_path = Path::new("./examples/sample_openapi.json");
-    let open_api_text = fs::read_to_string(open_api_path)
-        .unwrap_or_else(|e| panic!("Error while reading file {}", e));
 
-    // let open_api: OpenApi = serde_json::from_str(&open_api_text).expect("Could not retrieve paths");
-    // if let Some(path) = &open_api.paths{
-    //     for (url, path_data) in path {
-    //         println!("URL: {}", url);
-    //         println!("Path Data >>> {:?}", path_data);
-    //     }
-    // }
+fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    match matches.subcommand() {
+        Some(("scan", scan)) => {
+            let path = scan.get_one::<PathBuf>("spec").expect("spec is required");
+            let text = read_document(path)
+                .with_context(|| format!("Could not read {}", path.display()))?;
 
-    let json: Value = serde_json::from_str(&open_api_text).expect("Could not parse JSON text");
-
-    if let Some(paths) = json.get("paths") {
-        //println!("{:?}", paths);
-        println!("Paths exists in the json text");
-    } else {
-        println!("The 'paths' array does not exist in the JSON.");
-    }
-    //so I gotta write my own open api json parser...
-    //println!("Paths object as retrieved from the specification: {}", &paths.to_string());
-    let json_string = r#"
-    {
-        "name": "John Doe",
-        "age": 30,
-        "city": "Sample City"
-    }
-    "#;
-    let mut tokens: Vec<Token> = Vec::new();
-    let mut lexer: Lexer = Lexer::new(json_string);
-    while let Some(token) = lexer.next_token() {
-        tokens.push(token);
+            let header_policy = match scan.get_many::<String>("require-header") {
+                Some(headers) => HeaderPolicy::new(headers.cloned().collect()),
+                None => HeaderPolicy::default(),
+            };
+            let import_resolver = ImportResolver::new(
+                Some(path.clone()),
+                scan.get_one::<PathBuf>("catalogue").cloned(),
+            );
+            let mut scanner: Scanner = Scanner::new(text)
+                .with_header_policy(header_policy)
+                .with_import_resolver(import_resolver);
+            scanner.scan()?;
+            scanner.display()?;
+        }
+        Some(("soap", soap)) => match soap.subcommand() {
+            Some(("sample", sample)) => {
+                let wsdl = load_wsdl(sample)?;
+                let operation = sample
+                    .get_one::<String>("operation")
+                    .expect("operation is required");
+                let versions = match sample.get_one::<String>("soap-version").map(String::as_str) {
+                    Some("1.1") => vec![SoapVersion::Soap11],
+                    Some(_) => vec![SoapVersion::Soap12],
+                    None => vec![SoapVersion::Soap11, SoapVersion::Soap12],
+                };
+                for version in versions {
+                    let request = sample_request(&wsdl, operation, version)?;
+                    println!("### {}", version);
+                    println!("{}", request);
+                }
+            }
+            _ => unreachable!("a subcommand is required"),
+        },
+        Some(("convert", convert)) => match convert.subcommand() {
+            Some(("wsdl-to-openapi", wsdl_to_openapi)) => {
+                let wsdl = load_wsdl(wsdl_to_openapi)?;
+                let document = serde_json::to_string_pretty(&to_openapi(&wsdl))?;
+                match wsdl_to_openapi.get_one::<PathBuf>("output") {
+                    Some(output) => fs::write(output, document)
+                        .with_context(|| format!("Could not write {}", output.display()))?,
+                    None => println!("{}", document),
+                }
+            }
+            _ => unreachable!("a subcommand is required"),
+        },
+        _ => unreachable!("a subcommand is required"),
     }
 
-    println!("{:?}", tokens);
-
-    println!("Successfully retrieved open api file >>> {}", open_api_text);
+    Ok(())
+}
+/// Read and parse the `wsdl` argument, following its imports
+fn load_wsdl(matches: &ArgMatches) -> anyhow::Result<WsdlDocument> {
+    let path = matches.get_one::<PathBuf>("wsdl").expect("wsdl is required");
+    let text =
+        read_document(path).with_context(|| format!("Could not read {}", path.display()))?;
+    let mut wsdl = WsdlDocument::parse(&text)?;
+    ImportResolver::new(
+        Some(path.clone()),
+        matches.get_one::<PathBuf>("catalogue").cloned(),
+    )
+    .resolve(&mut wsdl);
+    Ok(wsdl)
+}
+fn main() {
+    let matches = cli().get_matches();
 
-    match run(&open_api_text) {
+    match run(&matches) {
         Ok(_) => {}
         Err(e) => {
             eprintln!("{} {:#}", "Error:", e);
@@ -74,6 +194,4 @@ fn main() {
             std::process::exit(1);
         }
     }
-
-    println!("The scanner has completed work");
 }
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::fmt::Display;
 
 use oapi::OApi;
 use oapi::OApiOperation;
@@ -8,6 +9,8 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::parser::*;
+use crate::rules::headers::HeaderPolicy;
+use crate::wsdl::imports::ImportResolver;
 
 const OPEN_API_INFO_STR: &str = "openapi";
 
@@ -23,6 +26,74 @@ pub enum IssueScoreImpact{
     StringPropertyWithoutMaxLength, // ScoreImpact - 2 [MEDIUM 2]
     StringPropertyWithoutREgexPattern, // scoreImpace - 3 [MEDIUM 3]
     NoServerBaseUrlDefined,
+    UnauthenticatedStateChangingOperation,
+    UnauthenticatedReadOperation,
+    OptionalAuthentication,
+    NoGlobalSecurityRequirement,
+    HttpBasicAuthScheme,
+    ApiKeyInQuery,
+    CookieApiKeyWithoutCsrf,
+    BearerWithoutFormat,
+    UnusedSecurityScheme,
+    UndefinedSecuritySchemeReference,
+    DeprecatedOAuthFlow,
+    InsecureOAuthUrl,
+    UnusedOAuthScope,
+    UndeclaredOAuthScope,
+    ReadScopeOnStateChangingOperation,
+    PlainHttpServer,
+    InternalServerAddress,
+    UnrestrictedServerVariable,
+    MixedHttpHttpsServers,
+    MissingErrorResponse,
+    NoRateLimitDocumented,
+    TooManyRequestsWithoutRetryAfter,
+    NoRateLimitHeaders,
+    CollectionWithoutPagination,
+    UnboundedCollection,
+    UnboundedPageSize,
+    UnauthenticatedObjectAccess,
+    SequentialObjectId,
+    ObjectAccessWithoutDenialResponse,
+    UnauthenticatedPrivilegedOperation,
+    PrivilegedOperationWithOrdinarySecurity,
+    WriteRequiresNoMoreThanRead,
+    UnrestrictedUrlInput,
+    RequestDrivenCallback,
+    RequestDrivenLink,
+    CredentialInUrl,
+    PersonalDataInUrl,
+    HardcodedSecret,
+    HighEntropyString,
+    WildcardRequestMediaType,
+    XmlRequestBody,
+    FileUploadWithoutSizeLimit,
+    FileUploadWithoutContentType,
+    MissingSecurityHeader,
+    CacheableTokenResponse,
+    WildcardCorsOnAuthenticatedOperation,
+    ErrorDetailsLeakage,
+    InconsistentErrorSchema,
+    UnauthenticatedDeprecatedOperation,
+    DeprecatedOperationWithoutSunset,
+    MultipleApiVersions,
+    MissingApiMetadata,
+    NumericValueTypedAsString,
+    NoSecurityPolicy,
+    OperationWithoutSecurityPolicy,
+    PlaintextUsernameToken,
+    MissingTimestamp,
+    MissingMessageSignature,
+    NonStandardPort,
+    InconsistentPortSecurity,
+    DangerousSoapOperation,
+    XmlDoctypeDeclaration,
+    XmlExternalEntity,
+    XmlEntityExpansion,
+    XmlWildcardContent,
+    MixedContent,
+    UnresolvedImport,
+    ImportCycle,
 }
 
 
@@ -32,6 +103,74 @@ impl IssueScoreImpact{
     pub const STRING_PROPERTY_WITHOUT_REGEX_PATTERN_VALUE: u8 = 3;
 
     pub const NO_SERVER_BASE_URL_DEFINED_VALUE: u8 = 10;
+    pub const UNAUTHENTICATED_STATE_CHANGING_OPERATION_VALUE: u8 = 10;
+    pub const UNAUTHENTICATED_READ_OPERATION_VALUE: u8 = 5;
+    pub const OPTIONAL_AUTHENTICATION_VALUE: u8 = 3;
+    pub const NO_GLOBAL_SECURITY_REQUIREMENT_VALUE: u8 = 5;
+    pub const HTTP_BASIC_AUTH_SCHEME_VALUE: u8 = 7;
+    pub const API_KEY_IN_QUERY_VALUE: u8 = 7;
+    pub const COOKIE_API_KEY_WITHOUT_CSRF_VALUE: u8 = 4;
+    pub const BEARER_WITHOUT_FORMAT_VALUE: u8 = 1;
+    pub const UNUSED_SECURITY_SCHEME_VALUE: u8 = 1;
+    pub const UNDEFINED_SECURITY_SCHEME_REFERENCE_VALUE: u8 = 10;
+    pub const DEPRECATED_OAUTH_FLOW_VALUE: u8 = 5;
+    pub const INSECURE_OAUTH_URL_VALUE: u8 = 8;
+    pub const UNUSED_OAUTH_SCOPE_VALUE: u8 = 1;
+    pub const UNDECLARED_OAUTH_SCOPE_VALUE: u8 = 5;
+    pub const READ_SCOPE_ON_STATE_CHANGING_OPERATION_VALUE: u8 = 5;
+    pub const PLAIN_HTTP_SERVER_VALUE: u8 = 8;
+    pub const INTERNAL_SERVER_ADDRESS_VALUE: u8 = 4;
+    pub const UNRESTRICTED_SERVER_VARIABLE_VALUE: u8 = 3;
+    pub const MIXED_HTTP_HTTPS_SERVERS_VALUE: u8 = 5;
+    pub const MISSING_ERROR_RESPONSE_VALUE: u8 = 1;
+    pub const NO_RATE_LIMIT_DOCUMENTED_VALUE: u8 = 3;
+    pub const TOO_MANY_REQUESTS_WITHOUT_RETRY_AFTER_VALUE: u8 = 1;
+    pub const NO_RATE_LIMIT_HEADERS_VALUE: u8 = 2;
+    pub const COLLECTION_WITHOUT_PAGINATION_VALUE: u8 = 2;
+    pub const UNBOUNDED_COLLECTION_VALUE: u8 = 5;
+    pub const UNBOUNDED_PAGE_SIZE_VALUE: u8 = 3;
+    pub const UNAUTHENTICATED_OBJECT_ACCESS_VALUE: u8 = 8;
+    pub const SEQUENTIAL_OBJECT_ID_VALUE: u8 = 3;
+    pub const OBJECT_ACCESS_WITHOUT_DENIAL_RESPONSE_VALUE: u8 = 1;
+    pub const UNAUTHENTICATED_PRIVILEGED_OPERATION_VALUE: u8 = 10;
+    pub const PRIVILEGED_OPERATION_WITH_ORDINARY_SECURITY_VALUE: u8 = 5;
+    pub const WRITE_REQUIRES_NO_MORE_THAN_READ_VALUE: u8 = 3;
+    pub const UNRESTRICTED_URL_INPUT_VALUE: u8 = 5;
+    pub const REQUEST_DRIVEN_CALLBACK_VALUE: u8 = 5;
+    pub const REQUEST_DRIVEN_LINK_VALUE: u8 = 2;
+    pub const CREDENTIAL_IN_URL_VALUE: u8 = 7;
+    pub const PERSONAL_DATA_IN_URL_VALUE: u8 = 3;
+    pub const HARDCODED_SECRET_VALUE: u8 = 10;
+    pub const HIGH_ENTROPY_STRING_VALUE: u8 = 2;
+    pub const WILDCARD_REQUEST_MEDIA_TYPE_VALUE: u8 = 3;
+    pub const XML_REQUEST_BODY_VALUE: u8 = 3;
+    pub const FILE_UPLOAD_WITHOUT_SIZE_LIMIT_VALUE: u8 = 5;
+    pub const FILE_UPLOAD_WITHOUT_CONTENT_TYPE_VALUE: u8 = 3;
+    pub const MISSING_SECURITY_HEADER_VALUE: u8 = 1;
+    pub const CACHEABLE_TOKEN_RESPONSE_VALUE: u8 = 5;
+    pub const WILDCARD_CORS_ON_AUTHENTICATED_OPERATION_VALUE: u8 = 7;
+    pub const ERROR_DETAILS_LEAKAGE_VALUE: u8 = 5;
+    pub const INCONSISTENT_ERROR_SCHEMA_VALUE: u8 = 1;
+    pub const UNAUTHENTICATED_DEPRECATED_OPERATION_VALUE: u8 = 5;
+    pub const DEPRECATED_OPERATION_WITHOUT_SUNSET_VALUE: u8 = 1;
+    pub const MULTIPLE_API_VERSIONS_VALUE: u8 = 3;
+    pub const MISSING_API_METADATA_VALUE: u8 = 1;
+    pub const NUMERIC_VALUE_TYPED_AS_STRING_VALUE: u8 = 2;
+    pub const NO_SECURITY_POLICY_VALUE: u8 = 10;
+    pub const OPERATION_WITHOUT_SECURITY_POLICY_VALUE: u8 = 5;
+    pub const PLAINTEXT_USERNAME_TOKEN_VALUE: u8 = 7;
+    pub const MISSING_TIMESTAMP_VALUE: u8 = 3;
+    pub const MISSING_MESSAGE_SIGNATURE_VALUE: u8 = 4;
+    pub const NON_STANDARD_PORT_VALUE: u8 = 1;
+    pub const INCONSISTENT_PORT_SECURITY_VALUE: u8 = 5;
+    pub const DANGEROUS_SOAP_OPERATION_VALUE: u8 = 2;
+    pub const XML_DOCTYPE_DECLARATION_VALUE: u8 = 3;
+    pub const XML_EXTERNAL_ENTITY_VALUE: u8 = 10;
+    pub const XML_ENTITY_EXPANSION_VALUE: u8 = 7;
+    pub const XML_WILDCARD_CONTENT_VALUE: u8 = 3;
+    pub const MIXED_CONTENT_VALUE: u8 = 2;
+    pub const UNRESOLVED_IMPORT_VALUE: u8 = 3;
+    pub const IMPORT_CYCLE_VALUE: u8 = 1;
 
 }
 impl WeightScore {
@@ -53,7 +192,7 @@ impl From<WeightScore> for FixableType {
         }
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Fixable {
     error: String,
     line: u64,
@@ -73,28 +212,102 @@ impl Fixable {
         }
     }
 }
+impl Display for Fixable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{:?}] [{:?}] {}", self.fixable_type, self.weight_score, self.error)
+    }
+}
+
+/// A table of scan results that doesn't fit a single fixable, e.g. an auth coverage matrix
+#[derive(Debug, Clone)]
+pub struct ReportTable {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+impl ReportTable {
+    pub fn new(title: impl Into<String>, headers: &[&str]) -> Self {
+        Self {
+            title: title.into(),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+}
+impl Display for ReportTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate().take(widths.len()) {
+                widths[i] = widths[i].max(cell.len());
+            }
+        }
+        writeln!(f, "{}", self.title)?;
+        let line = |cells: &[String]| -> String {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+        writeln!(f, "{}", line(&self.headers))?;
+        writeln!(f, "{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"))?;
+        for row in &self.rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub struct Scanner {
     //open_api:  OpenApi,
     text: String,
     fixables: Vec<Fixable>,
+    tables: Vec<ReportTable>,
+    header_policy: HeaderPolicy,
+    import_resolver: ImportResolver,
 }
 impl Scanner {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             text: text.into(),
             fixables: vec![],
+            tables: vec![],
+            header_policy: HeaderPolicy::default(),
+            import_resolver: ImportResolver::default(),
         }
     }
+    /// Scan against a custom response header policy instead of the default one
+    pub fn with_header_policy(mut self, header_policy: HeaderPolicy) -> Self {
+        self.header_policy = header_policy;
+        self
+    }
+    /// Follow WSDL and schema imports from local files, see [`ImportResolver`]
+    pub fn with_import_resolver(mut self, import_resolver: ImportResolver) -> Self {
+        self.import_resolver = import_resolver;
+        self
+    }
     /// Scan an input TEXT for Application Vulnerabilities according to OWASP-Top-10
     pub fn scan(&mut self) -> Result<Vec<Fixable>, ParserError> {
-        let mut parser = Parser::new(&self.text);
+        let mut parser = Parser::new(&self.text)
+            .with_header_policy(self.header_policy.clone())
+            .with_import_resolver(self.import_resolver.clone());
 
         let spec_type: ApiSpecificationType = guess_spec_type_from_text(&self.text);
 
         match parser.parse(&spec_type) {
-            Ok(fixes) => Ok(fixes),
+            Ok(fixes) => {
+                self.fixables = fixes.clone();
+                self.tables = parser.tables().to_vec();
+                Ok(fixes)
+            }
             Err(e) => Err(ParserError::ParseFailed(
                 format!("error parsing {} type specification {}", spec_type, e).into(),
             )),
@@ -102,9 +315,13 @@ impl Scanner {
     }
     /// Display the results/output of a scan in the CLI console or API caller in the future
     pub fn display(&mut self) -> anyhow::Result<()> {
-        println!("{:?}", self);
         println!("Here are some discovered security vulnerabilities after scanning code against OWASP-top-10");
-        println!("{:?}", self.fixables);
+        for fixable in &self.fixables {
+            println!("{}", fixable);
+        }
+        for table in &self.tables {
+            println!("\n{}", table);
+        }
 
         Ok(())
     }
@@ -126,7 +343,7 @@ fn guess_spec_type_from_text(text: &str) -> ApiSpecificationType {
         Err(e) => {
             //either it's not a JSON -> it's prolly an XML in case of WSDl...
 
-            if is_wsdl_spec_v2(text).unwrap() {
+            if is_wsdl_spec_v2(text) {
                 ApiSpecificationType::SoapWSDL
             } else {
                 println!("Unknown Api specification type detected. XML is not a valid SOAP WSDL specification. {}", e);
@@ -241,12 +458,54 @@ pub struct Property {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SecurityScheme {
+    #[serde(rename = "type")]
     pub type_: String, // Use "type_" for the "type" field
-    pub description: String,
-    pub scheme: String,
-    pub bearer_format: String,
-    // Add other fields
+    pub description: Option<String>,
+    /// HTTP authorization scheme, `basic`, `bearer` etc. (type `http` only)
+    pub scheme: Option<String>,
+    pub bearer_format: Option<String>,
+    /// header, query or cookie parameter name (type `apiKey` only)
+    pub name: Option<String>,
+    #[serde(rename = "in")]
+    pub in_: Option<String>,
+    pub open_id_connect_url: Option<String>,
+    /// type `oauth2` only
+    pub flows: Option<OAuthFlows>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlows {
+    pub implicit: Option<OAuthFlow>,
+    pub password: Option<OAuthFlow>,
+    pub client_credentials: Option<OAuthFlow>,
+    pub authorization_code: Option<OAuthFlow>,
+}
+impl OAuthFlows {
+    /// Every declared flow along with its name as it appears in the specification
+    pub fn declared(&self) -> Vec<(&'static str, &OAuthFlow)> {
+        [
+            ("implicit", &self.implicit),
+            ("password", &self.password),
+            ("clientCredentials", &self.client_credentials),
+            ("authorizationCode", &self.authorization_code),
+        ]
+        .into_iter()
+        .filter_map(|(name, flow)| flow.as_ref().map(|flow| (name, flow)))
+        .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlow {
+    pub authorization_url: Option<String>,
+    pub token_url: Option<String>,
+    pub refresh_url: Option<String>,
+    #[serde(default)]
+    pub scopes: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -315,3 +574,30 @@ pub enum EndpointResponse {
     Http500InternalServerError(String, String, String),
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_table_pads_columns_to_their_widest_cell() {
+        let mut table = ReportTable::new("Auth coverage", &["Endpoint", "Source"]);
+        table.add_row(vec!["GET /mangoes".to_string(), "root".to_string()]);
+        table.add_row(vec!["DELETE /mangoes/{id}".to_string(), "none".to_string()]);
+        assert_eq!(
+            table.to_string(),
+            "Auth coverage\n\
+             Endpoint             | Source\n\
+             ---------------------+-------\n\
+             GET /mangoes         | root\n\
+             DELETE /mangoes/{id} | none\n"
+        );
+    }
+
+    #[test]
+    fn report_table_ignores_cells_without_a_header() {
+        let mut table = ReportTable::new("Inventory", &["Operation"]);
+        table.add_row(vec!["Place".to_string(), "unexpected".to_string()]);
+        assert_eq!(table.to_string(), "Inventory\nOperation\n---------\nPlace\n");
+    }
+}
@@ -7,7 +7,32 @@ extern crate serde_xml_rs;
 
 use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-use crate::openapi::{EndpointPath, Fixable, IssueScoreImpact, OpenApi, OpenApiDoc, WeightScore};
+use crate::openapi::{EndpointPath, Fixable, IssueScoreImpact, OpenApi, OpenApiDoc, ReportTable, WeightScore};
+use crate::rules::auth::check_security_requirements;
+use crate::rules::bola::check_object_level_authorization;
+use crate::rules::content::check_request_content;
+use crate::rules::error_responses::check_error_responses;
+use crate::rules::function_level::check_function_level_authorization;
+use crate::rules::headers::{check_security_headers, HeaderPolicy};
+use crate::rules::inventory::check_inventory;
+use crate::rules::oauth::check_oauth_flows;
+use crate::rules::rate_limit::check_rate_limiting;
+use crate::rules::responses::check_response_codes;
+use crate::rules::schemes::check_security_schemes;
+use crate::rules::sensitive_data::check_sensitive_data_in_url;
+use crate::rules::servers::check_servers;
+use crate::rules::ssrf::check_ssrf;
+use crate::rules::wsdl::endpoints::check_soap_endpoints;
+use crate::rules::wsdl::imports::check_imports;
+use crate::rules::wsdl::operations::check_soap_operations;
+use crate::rules::wsdl::policy::check_ws_security;
+use crate::rules::wsdl::schema::check_schema_constraints;
+use crate::rules::wsdl::xml::{check_doctype, check_xml_attack_surface};
+use crate::secrets::{scan_json_document, scan_xml_document};
+use crate::wsdl::dtd::find_doctype;
+use crate::wsdl::imports::ImportResolver;
+use crate::wsdl::normalise::to_openapi;
+use crate::wsdl::{is_wsdl_document, WsdlDocument};
 use std::error::Error;
 
 #[derive(Debug, thiserror::Error)]
@@ -15,9 +40,9 @@ pub enum ParserError {
     #[error("Invalid input text. Either input specification text is empty or not valid")]
     InvalidInputText,
     #[error(
-        "Invalid SOAP specification (WSDL definition) file. Please fix the errors and try again."
+        "Invalid SOAP specification (WSDL definition) file: {0}. Please fix the errors and try again."
     )]
-    InvalidSOAPSpec,
+    InvalidSOAPSpec(String),
     #[error("Invalid Specification Type. Allowed Specification Types are in the code somewhere.")]
     InvalidSpecificationType,
     #[error("Error occurred while parsing input specification text: {0}")]
@@ -35,13 +60,13 @@ pub enum ParserError {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Document{
     OpenAPI(OApiDocument),
-
+    Wsdl(WsdlDocument),
 }
 impl Display for Document {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Document::OpenAPI(_) => write!(f, "Open API"),
-            _ => unimplemented!(),
+            Document::Wsdl(_) => write!(f, "WSDL"),
         }
     }
 }
@@ -49,7 +74,7 @@ impl Into<String> for Document{
     fn into(self) -> String {
         match self{
             Document::OpenAPI(_) => "OpenAPI".into(),
-            _ => unimplemented!()
+            Document::Wsdl(_) => "WSDL".into(),
         }
     }
 }
@@ -85,6 +110,9 @@ pub struct Parser<'a> {
     text: &'a str,
     start: usize,
     end: usize,
+    tables: Vec<ReportTable>,
+    header_policy: HeaderPolicy,
+    import_resolver: ImportResolver,
 }
 
 impl<'a> Parser<'a> {
@@ -93,8 +121,25 @@ impl<'a> Parser<'a> {
             text,
             start: 0,
             end: 0,
+            tables: vec![],
+            header_policy: HeaderPolicy::default(),
+            import_resolver: ImportResolver::default(),
         }
     }
+    /// Replace the default response header policy
+    pub fn with_header_policy(mut self, header_policy: HeaderPolicy) -> Self {
+        self.header_policy = header_policy;
+        self
+    }
+    /// Resolve WSDL and schema imports with `import_resolver` instead of leaving them out
+    pub fn with_import_resolver(mut self, import_resolver: ImportResolver) -> Self {
+        self.import_resolver = import_resolver;
+        self
+    }
+    /// Report tables (auth coverage matrix etc.) collected by the last call to `parse`
+    pub fn tables(&self) -> &[ReportTable] {
+        &self.tables
+    }
 
     pub fn parse(&mut self, spec_type: &ApiSpecificationType) -> Result<Vec<Fixable>, ParserError> {
         if self.text.is_empty() {
@@ -104,11 +149,11 @@ impl<'a> Parser<'a> {
 
         match *spec_type {
             ApiSpecificationType::OpenApiRest => {
-                fixables = parse_open_api_rest(self.text).unwrap();
+                fixables = parse_open_api_rest(self.text, &self.header_policy, &mut self.tables).unwrap();
                 Ok(fixables)
             }
             ApiSpecificationType::SoapWSDL => {
-                fixables = parse_soap_wsdl(self.text).unwrap();
+                fixables = parse_soap_wsdl(self.text, &self.import_resolver, &mut self.tables)?;
                 Ok(fixables)
             }
             _ => Err(ParserError::InvalidSpecificationType),
@@ -116,8 +161,45 @@ impl<'a> Parser<'a> {
     }
 }
 
-fn parse_soap_wsdl(text: &str) -> Result<Vec<Fixable>, ParserError> {
-    todo!()
+fn parse_soap_wsdl(text: &str, import_resolver: &ImportResolver, tables: &mut Vec<ReportTable>) -> Result<Vec<Fixable>, ParserError> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut final_security_category_score: u8 = 30;
+    let mut final_data_validation_category_score: u8 = 70;
+
+    //the parser refuses documents using DTD entities, report the DTD before it gets a chance to
+    if let Some(doctype) = find_doctype(text) {
+        fixables.extend(check_doctype(&doctype, &mut final_security_category_score));
+    }
+    let mut wsdl = match WsdlDocument::parse(text) {
+        Ok(wsdl) => wsdl,
+        Err(e) if !fixables.is_empty() => {
+            fixables.push(Fixable::new(format!("The rest of the document was not analysed: {}", e), 0, WeightScore::Medium));
+            return Ok(fixables);
+        }
+        Err(e) => return Err(e),
+    };
+    import_resolver.resolve(&mut wsdl);
+    fixables.extend(check_imports(&wsdl, &mut final_data_validation_category_score, tables));
+    fixables.extend(check_ws_security(&wsdl, &mut final_security_category_score, tables));
+    fixables.extend(check_soap_endpoints(&wsdl, &mut final_security_category_score));
+    fixables.extend(check_soap_operations(&wsdl, &mut final_security_category_score, tables));
+    fixables.extend(check_xml_attack_surface(&wsdl, &mut final_security_category_score));
+
+    //the REST auth coverage, input validation and inventory rules run over the normalised operations
+    let normalised = to_openapi(&wsdl);
+    fixables.extend(check_security_requirements(&normalised, &mut final_security_category_score, tables));
+    fixables.extend(check_ssrf(&normalised, &mut final_security_category_score));
+    fixables.extend(check_request_content(&normalised, &mut final_security_category_score));
+    fixables.extend(check_error_responses(&normalised, &mut final_security_category_score));
+    fixables.extend(check_inventory(&normalised, &mut final_security_category_score, tables));
+
+    let document = Document::Wsdl(wsdl);
+    fixables.extend(do_data_validations(&document, &mut final_data_validation_category_score, ApiSpecificationType::SoapWSDL)?);
+
+    //secrets hide in attribute values and text nodes alike, scan the raw document
+    fixables.extend(scan_xml_document(text, &mut final_security_category_score));
+
+    Ok(fixables)
 }
 
 /// <p>Use this to parse a plain OpenApi spec text to an OpenAPIDoc object<br></p>
@@ -126,7 +208,16 @@ fn parse_soap_wsdl(text: &str) -> Result<Vec<Fixable>, ParserError> {
 fn parse_json_open_api(text: &str) -> Result<OApiDocument, ParserError> {
     // let mut open_api_doc: OpenApiDoc = OpenApiDoc::new("3.0.1");
     //let open_api: OpenApi = serde_json::from_str(&text).expect("Could not retrieve paths");
-    let open_api: OApiDocument = serde_json::from_str(text).map_err(|e| {
+    let mut value: Value = serde_json::from_str(text).map_err(|e| {
+        ParserError::ParseFailed(format!("Failed to parse open api JSON specification {}", e))
+    })?;
+    //oapi expects the root `security` to be a map of schemes while the spec defines an array of
+    //security requirements, so drop it here... the rules read it from the raw JSON instead
+    if let Some(root) = value.as_object_mut() {
+        root.remove("security");
+    }
+    strip_unsupported_formats(&mut value);
+    let open_api: OApiDocument = serde_json::from_value(value).map_err(|e| {
         ParserError::ParseFailed(format!("Failed to parse open api JSON specification {}", e))
     })?;
 
@@ -137,12 +228,35 @@ fn parse_json_open_api(text: &str) -> Result<OApiDocument, ParserError> {
 
     Ok(open_api)
 }
+/// oapi only accepts the string/numeric formats it has enum variants for and rejects the whole
+/// document on anything else (`uuid`, `uri`, `email`...). Formats are free form in the spec, so drop
+/// the unknown ones from the copy handed to oapi, rules read them from the raw JSON.
+fn strip_unsupported_formats(value: &mut Value) {
+    const SUPPORTED_FORMATS: [&str; 9] = [
+        "byte", "binary", "date", "date-time", "password", "int32", "int64", "float", "double",
+    ];
+    match value {
+        Value::Object(map) => {
+            let unsupported = map
+                .get("format")
+                .and_then(Value::as_str)
+                .map(|format| !SUPPORTED_FORMATS.contains(&format))
+                .unwrap_or(false);
+            if unsupported && map.get("type").map(Value::is_string).unwrap_or(false) {
+                map.remove("format");
+            }
+            map.values_mut().for_each(strip_unsupported_formats);
+        }
+        Value::Array(values) => values.iter_mut().for_each(strip_unsupported_formats),
+        _ => {}
+    }
+}
 fn parse_yaml_open_api(text: &str) -> Result<(), ParserError> {
     Ok(())
 }
 ///Use this to parse an OPENAPI specification type document
 /// <br>It takes a string slice of the content of the OPEN Spec a.k.a swagger.json
-fn parse_open_api_rest(text: &str) -> Result<Vec<Fixable>, ParserError> {
+fn parse_open_api_rest(text: &str, header_policy: &HeaderPolicy, tables: &mut Vec<ReportTable>) -> Result<Vec<Fixable>, ParserError> {
     let mut fixables: Vec<Fixable> = Vec::new();
     let mut final_core: u8 = 100; //final weight score...
     //two options - 1... we penalize them for each error...only from the total max obtainable
@@ -150,7 +264,6 @@ fn parse_open_api_rest(text: &str) -> Result<Vec<Fixable>, ParserError> {
     let mut final_data_validation_category_score: u8 = 70; 
     let mut final_security_category_score: u8 = 30;
 
-    println!("Input Text: {}", text);
     //grabables
     let master_piece: Value = serde_json::from_str(text).map_err(|e| {
         ParserError::JSONParseError(format!("Error parsing Open API JSON spec: {}", e))
@@ -177,16 +290,30 @@ fn parse_open_api_rest(text: &str) -> Result<Vec<Fixable>, ParserError> {
         );
         fixables.push(Fixable::new("Invalid server. You must provide a server BASEURL for your API. Read OpenAPI specification standards for more information", 0, WeightScore::Critical));
     }
+    fixables.extend(check_servers(&master_piece, &mut final_security_category_score));
     
     //PATHs -> a.k.a Endpoints -> Object -> has Other objects inside one for each endpoint
     // DATA validations...
-    do_data_validations(&Document::OpenAPI(document.clone()), &mut final_data_validation_category_score, ApiSpecificationType::OpenApiRest)?;
+    fixables.extend(do_data_validations(&Document::OpenAPI(document.clone()), &mut final_data_validation_category_score, ApiSpecificationType::OpenApiRest)?);
+    fixables.extend(check_response_codes(&master_piece, &mut final_data_validation_category_score, tables));
 
     //COMPONENTS -> Logic Objects -> Request Objects' Schemas... containing fields, validation, regex, strings etc
 
     //SECURITY -> Array of Security Sechemes Objects -> Actually this part bears 30% of total score...
+    fixables.extend(check_security_requirements(&master_piece, &mut final_security_category_score, tables));
+    fixables.extend(check_security_schemes(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_oauth_flows(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_rate_limiting(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_object_level_authorization(&master_piece, &mut final_security_category_score, tables));
+    fixables.extend(check_function_level_authorization(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_ssrf(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_sensitive_data_in_url(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_request_content(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_security_headers(&master_piece, header_policy, &mut final_security_category_score));
+    fixables.extend(check_error_responses(&master_piece, &mut final_security_category_score));
+    fixables.extend(check_inventory(&master_piece, &mut final_security_category_score, tables));
+    fixables.extend(scan_json_document(&master_piece, &mut final_security_category_score));
 
-    println!("Fixables / Report {:?}", fixables);
     Ok(fixables)
 }
 
@@ -198,12 +325,7 @@ fn do_data_validations(document: &Document, final_data_validation_category_score
             match document{
                 Document::OpenAPI(d) => {
                     if let Some(path) = Some(d.paths()){
-                        println!("Displaying path info in console: {:?}", path);
-                        println!("-------------------------------------------------\n");
                         for(url, path_item) in path{
-                            println!("URL for this path item: {}", url);
-                            println!("Path Item for this current URL: {:?}", path_item);
-                            println!("--------------------------------------------------------------\n");
                             
 
                             if let Some(get_op) = path_item.get(){
@@ -228,7 +350,14 @@ fn do_data_validations(document: &Document, final_data_validation_category_score
                 _ => return Err(ParserError::IncompatibleSpecificationAndDocumentType(spec_type.to_string(), document.to_string()))
             }
         },
-        ApiSpecificationType::SoapWSDL => todo!(),
+        ApiSpecificationType::SoapWSDL => {
+            match document{
+                Document::Wsdl(wsdl) => {
+                    fixables.extend(check_schema_constraints(wsdl, final_data_validation_category_score));
+                }
+                _ => return Err(ParserError::IncompatibleSpecificationAndDocumentType(spec_type.to_string(), document.to_string()))
+            }
+        },
         _ => unimplemented!()
     }
 
@@ -249,7 +378,7 @@ fn has_non_empty_array_item(json_value: &Value, array_key: &str, array_item: &st
     }
     false
 }
-fn modify_score(final_score: &mut u8, weight_value: u8) {
+pub(crate) fn modify_score(final_score: &mut u8, weight_value: u8) {
     *final_score = final_score.saturating_sub(weight_value);
 }
 // pub fn is_wsdl_spec(xml_str: &str) -> Result<bool, serde_xml_rs::Error> {
@@ -270,12 +399,9 @@ fn modify_score(final_score: &mut u8, weight_value: u8) {
 //         }
 //     }
 // }
-///usually the first at least 50 chars of WSDL should have the tag <definitions
-pub fn is_wsdl_spec_v2(xml_str: &str) -> Result<bool, serde_xml_rs::Error> {
-    Ok(xml_str[0..50]
-        .to_lowercase()
-        .replace(' ', "")
-        .contains("<definitions"))
+///the root element has to be a WSDL 1.1 `definitions` or a WSDL 2.0 `description`, whatever its prefix
+pub fn is_wsdl_spec_v2(xml_str: &str) -> bool {
+    is_wsdl_document(xml_str)
 }
 
 // #[derive(Debug, serde::Deserialize)]
@@ -289,3 +415,31 @@ struct WsdlDefinitions {
     // You can add more fields if needed
     // For example, check for specific elements within <definitions>
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dtd_findings_survive_a_document_failing_to_parse() {
+        let text = r#"<?xml version="1.0"?>
+<!DOCTYPE definitions [
+  <!ENTITY xxe SYSTEM "file:///etc/passwd">
+  <!ENTITY lol "lol">
+  <!ENTITY lol2 "&lol;&lol;">
+]>
+<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:t">
+  <documentation>&lol2;</documentation>
+</definitions>"#;
+        let fixables = parse_soap_wsdl(text, &ImportResolver::default(), &mut vec![]).unwrap();
+        let messages: Vec<String> = fixables.iter().map(|f| f.to_string()).collect();
+        assert!(messages.iter().any(|m| m.contains("Entity `xxe` (line 3) is an external entity")));
+        assert!(messages.iter().any(|m| m.contains("Entity `lol2` (line 5) expands to other entities")));
+        assert!(messages.iter().any(|m| m.contains("Unexpected entity: lol2")));
+    }
+
+    #[test]
+    fn documents_without_doctype_still_fail_to_parse() {
+        assert!(parse_soap_wsdl("<definitions", &ImportResolver::default(), &mut vec![]).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{operations, OperationRef};
+
+/// Where the security requirement in effect for an operation comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecuritySource {
+    /// inherited from the root level `security` array
+    Root,
+    /// declared by the operation itself
+    Operation,
+    /// the operation declares `security: []`, opting out of the root requirement
+    OptOut,
+    /// neither the root nor the operation declare any requirement
+    Missing,
+}
+impl std::fmt::Display for SecuritySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecuritySource::Root => write!(f, "root"),
+            SecuritySource::Operation => write!(f, "operation"),
+            SecuritySource::OptOut => write!(f, "operation opt-out"),
+            SecuritySource::Missing => write!(f, "none"),
+        }
+    }
+}
+
+/// One security requirement object, a scheme name mapped to the scopes it needs.
+/// <br>Every scheme of a requirement must be satisfied together.
+pub type SecurityRequirement = Vec<(String, Vec<String>)>;
+
+/// The security in effect for a single operation.
+/// <br>`requirements` are alternatives, satisfying any one of them grants access.
+#[derive(Debug, Clone)]
+pub struct EffectiveSecurity {
+    pub source: SecuritySource,
+    pub requirements: Vec<SecurityRequirement>,
+}
+
+impl EffectiveSecurity {
+    /// Resolve the effective security of `operation`: an operation level `security`
+    /// array (including an empty one) overrides the root level one.
+    pub fn of(spec: &Value, operation: &Value) -> Self {
+        if let Some(security) = operation.get("security") {
+            let requirements = parse_requirements(security);
+            let source = if requirements.is_empty() {
+                SecuritySource::OptOut
+            } else {
+                SecuritySource::Operation
+            };
+            return Self {
+                source,
+                requirements,
+            };
+        }
+        let requirements = spec
+            .get("security")
+            .map(parse_requirements)
+            .unwrap_or_default();
+        let source = if requirements.is_empty() {
+            SecuritySource::Missing
+        } else {
+            SecuritySource::Root
+        };
+        Self {
+            source,
+            requirements,
+        }
+    }
+    /// No requirement at all applies to the operation
+    pub fn is_unauthenticated(&self) -> bool {
+        self.requirements.is_empty()
+    }
+    /// An empty requirement object (`{}`) is one of the alternatives, so anonymous callers are let in
+    pub fn allows_anonymous(&self) -> bool {
+        self.requirements.iter().any(|r| r.is_empty())
+    }
+    /// Human readable form, e.g. `Bearer | OAuth2[read:mangoes] + ApiKey | anonymous`
+    pub fn describe(&self) -> String {
+        if self.is_unauthenticated() {
+            return "none".to_string();
+        }
+        self.requirements
+            .iter()
+            .map(|requirement| {
+                if requirement.is_empty() {
+                    return "anonymous".to_string();
+                }
+                requirement
+                    .iter()
+                    .map(|(name, scopes)| {
+                        if scopes.is_empty() {
+                            name.clone()
+                        } else {
+                            format!("{}[{}]", name, scopes.join(","))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+pub fn parse_requirements(security: &Value) -> Vec<SecurityRequirement> {
+    security
+        .as_array()
+        .map(|requirements| {
+            requirements
+                .iter()
+                .filter_map(Value::as_object)
+                .map(|requirement| {
+                    requirement
+                        .iter()
+                        .map(|(name, scopes)| {
+                            let scopes = scopes
+                                .as_array()
+                                .map(|s| {
+                                    s.iter()
+                                        .filter_map(Value::as_str)
+                                        .map(String::from)
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            (name.clone(), scopes)
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Resolve the effective security of every operation, flag operations anyone can call and
+/// build the auth coverage matrix.
+pub fn check_security_requirements(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut matrix = ReportTable::new(
+        "Auth coverage matrix",
+        &["Endpoint", "Method", "Effective security", "Source"],
+    );
+
+    let has_root_security = spec
+        .get("security")
+        .and_then(Value::as_array)
+        .map(|requirements| !requirements.is_empty())
+        .unwrap_or(false);
+    if !has_root_security {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::NO_GLOBAL_SECURITY_REQUIREMENT_VALUE,
+        );
+        fixables.push(Fixable::new("No global security requirement defined. Operations are unauthenticated unless they declare their own `security`. Define a root level `security` array so endpoints are secure by default", 0, WeightScore::High));
+    }
+
+    for op in operations(spec) {
+        let security = EffectiveSecurity::of(spec, op.operation);
+        matrix.add_row(vec![
+            op.path.to_string(),
+            op.method.to_uppercase(),
+            security.describe(),
+            security.source.to_string(),
+        ]);
+        check_operation_security(&op, &security, final_security_category_score, &mut fixables);
+    }
+
+    tables.push(matrix);
+    fixables
+}
+
+fn check_operation_security(
+    op: &OperationRef,
+    security: &EffectiveSecurity,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    if security.is_unauthenticated() {
+        let reason = match security.source {
+            SecuritySource::OptOut => "explicitly opts out of authentication with `security: []`",
+            _ => "has no security requirement",
+        };
+        if op.is_state_changing() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNAUTHENTICATED_STATE_CHANGING_OPERATION_VALUE,
+            );
+            fixables.push(Fixable::new(format!("State changing operation {} {}. Anyone can modify data through this endpoint, require authentication for it", op.label(), reason), 0, WeightScore::Critical));
+        } else {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNAUTHENTICATED_READ_OPERATION_VALUE,
+            );
+            let weight = match security.source {
+                SecuritySource::OptOut => WeightScore::Medium,
+                _ => WeightScore::High,
+            };
+            fixables.push(Fixable::new(
+                format!(
+                    "Operation {} {}. Make sure it is meant to be public",
+                    op.label(),
+                    reason
+                ),
+                0,
+                weight,
+            ));
+        }
+    } else if security.allows_anonymous() {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::OPTIONAL_AUTHENTICATION_VALUE,
+        );
+        let weight = if op.is_state_changing() {
+            WeightScore::High
+        } else {
+            WeightScore::Low
+        };
+        fixables.push(Fixable::new(format!("Operation {} accepts anonymous callers through an empty security requirement `{{}}`. Authentication is optional for this endpoint", op.label()), 0, weight));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec_with_root_security() -> Value {
+        json!({ "security": [{ "Bearer": [] }] })
+    }
+
+    #[test]
+    fn operations_without_security_inherit_the_root_requirement() {
+        let security = EffectiveSecurity::of(&spec_with_root_security(), &json!({}));
+        assert_eq!(security.source, SecuritySource::Root);
+        assert_eq!(security.describe(), "Bearer");
+    }
+
+    #[test]
+    fn operation_security_overrides_the_root_requirement() {
+        let operation = json!({ "security": [{ "OAuth2": ["read:mangoes"], "ApiKey": [] }, {}] });
+        let security = EffectiveSecurity::of(&spec_with_root_security(), &operation);
+        assert_eq!(security.source, SecuritySource::Operation);
+        assert_eq!(
+            security.describe(),
+            "ApiKey + OAuth2[read:mangoes] | anonymous"
+        );
+        assert!(security.allows_anonymous());
+    }
+
+    #[test]
+    fn an_empty_operation_security_opts_out_of_the_root_requirement() {
+        let operation = json!({ "security": [] });
+        let security = EffectiveSecurity::of(&spec_with_root_security(), &operation);
+        assert_eq!(security.source, SecuritySource::OptOut);
+        assert!(security.is_unauthenticated());
+
+        let security = EffectiveSecurity::of(&json!({}), &json!({}));
+        assert_eq!(security.source, SecuritySource::Missing);
+        assert!(security.is_unauthenticated());
+    }
+}
@@ -0,0 +1,188 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::EffectiveSecurity;
+use crate::rules::{operations, resolve_ref};
+
+/// Path parameter names that identify an object, e.g. `id`, `userId`, `account_id`, `accountNo`
+const OBJECT_ID_PATTERN: &str = r"^(?i:id|uuid|guid|key)$|(?:Id|ID|_id|-id|Uuid|UUID|_uuid|Guid|_guid|No|_no|Number|_number|Num|_num|Key|_key)$";
+
+/// OWASP API1 (broken object level authorization): operations addressing an object through an
+/// ID in their path, flagged when unauthenticated, guessable or without a documented 403/404.
+pub fn check_object_level_authorization(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let object_id = Regex::new(OBJECT_ID_PATTERN).unwrap();
+    let mut review_list = ReportTable::new(
+        "BOLA review list",
+        &[
+            "Endpoint",
+            "Method",
+            "ID parameters",
+            "ID type",
+            "Security",
+            "Concerns",
+        ],
+    );
+
+    for op in operations(spec) {
+        let id_parameters: Vec<&Value> = op
+            .parameters(spec)
+            .into_iter()
+            .filter(|p| p.get("in").and_then(Value::as_str) == Some("path"))
+            .filter(|p| {
+                object_id.is_match(p.get("name").and_then(Value::as_str).unwrap_or_default())
+            })
+            .collect();
+        if id_parameters.is_empty() {
+            continue;
+        }
+        let names: Vec<&str> = id_parameters
+            .iter()
+            .filter_map(|p| p.get("name").and_then(Value::as_str))
+            .collect();
+        let types: Vec<String> = id_parameters.iter().map(|p| id_type(spec, p)).collect();
+        let security = EffectiveSecurity::of(spec, op.operation);
+        let mut concerns: Vec<String> = Vec::new();
+
+        if security.is_unauthenticated() {
+            concerns.push("unauthenticated".to_string());
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNAUTHENTICATED_OBJECT_ACCESS_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} exposes objects by ID ({}) without authentication. Anyone who guesses or enumerates an ID can access the object", op.label(), names.join(", ")), 0, WeightScore::High));
+        }
+        let sequential: Vec<&str> = names
+            .iter()
+            .zip(&types)
+            .filter(|(_, type_)| type_.starts_with("integer"))
+            .map(|(name, _)| *name)
+            .collect();
+        if !sequential.is_empty() {
+            concerns.push("sequential ID".to_string());
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::SEQUENTIAL_OBJECT_ID_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} identifies objects with integer IDs ({}). Sequential IDs are trivial to enumerate, prefer UUIDs (`format: uuid`) and verify object ownership on every request", op.label(), sequential.join(", ")), 0, WeightScore::Medium));
+        }
+        let missing: Vec<&str> = ["403", "404"]
+            .into_iter()
+            .filter(|code| !op.documents_response(code))
+            .collect();
+        if !missing.is_empty() {
+            concerns.push(format!("no {}", missing.join("/")));
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::OBJECT_ACCESS_WITHOUT_DENIAL_RESPONSE_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} addresses objects by ID but documents no {} response. It's unclear how access to another user's object is denied", op.label(), missing.join("/")), 0, WeightScore::Low));
+        }
+
+        review_list.add_row(vec![
+            op.path.to_string(),
+            op.method.to_uppercase(),
+            names.join(", "),
+            types.join(", "),
+            security.describe(),
+            concerns.join(", "),
+        ]);
+    }
+
+    if !review_list.rows.is_empty() {
+        tables.push(review_list);
+    }
+    fixables
+}
+
+/// `type` of the parameter schema, with its `format` if any, e.g. `string(uuid)`
+fn id_type(spec: &Value, parameter: &Value) -> String {
+    let Some(schema) = parameter.get("schema").map(|s| resolve_ref(spec, s)) else {
+        return "unknown".to_string();
+    };
+    let type_ = schema
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    match schema.get("format").and_then(Value::as_str) {
+        Some(format) => format!("{}({})", type_, format),
+        None => type_.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec_with_operation(path: &str, operation: Value) -> Value {
+        json!({ "paths": { path: { "get": operation } } })
+    }
+
+    fn path_parameter(name: &str, schema: Value) -> Value {
+        json!({ "name": name, "in": "path", "required": true, "schema": schema })
+    }
+
+    #[test]
+    fn unauthenticated_sequential_ids_without_denial_responses_are_reported() {
+        let spec = spec_with_operation(
+            "/users/{userId}",
+            json!({
+                "parameters": [path_parameter("userId", json!({ "type": "integer" }))],
+                "responses": { "200": {} }
+            }),
+        );
+        let mut tables = vec![];
+        let found: Vec<String> = check_object_level_authorization(&spec, &mut 30, &mut tables)
+            .iter()
+            .map(Fixable::to_string)
+            .collect();
+        assert_eq!(found.len(), 3);
+        assert!(found[0].contains("without authentication"));
+        assert!(found[1].contains("integer IDs (userId)"));
+        assert!(found[2].contains("documents no 403/404 response"));
+        assert_eq!(
+            tables[0].rows[0][5],
+            "unauthenticated, sequential ID, no 403/404"
+        );
+    }
+
+    #[test]
+    fn protected_uuid_ids_are_only_listed_for_review() {
+        let spec = spec_with_operation(
+            "/accounts/{account_id}",
+            json!({
+                "security": [{ "Bearer": [] }],
+                "parameters": [path_parameter("account_id", json!({ "type": "string", "format": "uuid" }))],
+                "responses": { "200": {}, "403": {}, "404": {} }
+            }),
+        );
+        let mut tables = vec![];
+        assert!(check_object_level_authorization(&spec, &mut 30, &mut tables).is_empty());
+        assert_eq!(tables[0].rows[0][3], "string(uuid)");
+    }
+
+    #[test]
+    fn parameters_that_are_not_object_ids_are_ignored() {
+        let spec = spec_with_operation(
+            "/reports/{slug}/{monkey}",
+            json!({
+                "parameters": [
+                    path_parameter("slug", json!({ "type": "string" })),
+                    path_parameter("monkey", json!({ "type": "integer" }))
+                ],
+                "responses": { "200": {} }
+            }),
+        );
+        let mut tables = vec![];
+        assert!(check_object_level_authorization(&spec, &mut 30, &mut tables).is_empty());
+        assert!(tables.is_empty());
+    }
+}
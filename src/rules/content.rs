@@ -0,0 +1,174 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{operations, resolve_ref, visit_properties, OperationRef};
+
+/// Media types accepting arbitrary content
+const WILDCARD_MEDIA_TYPES: [&str; 2] = ["*/*", "application/octet-stream"];
+
+/// Request body media types: wildcards, file uploads without size or content type restrictions
+/// and XML bodies exposed to XXE.
+pub fn check_request_content(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+
+    for op in operations(spec) {
+        let Some(request_body) = op.operation.get("requestBody") else {
+            continue;
+        };
+        let Some(content) = resolve_ref(spec, request_body)
+            .get("content")
+            .and_then(Value::as_object)
+        else {
+            continue;
+        };
+        for (media_type, media) in content {
+            let essence = media_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase();
+
+            if WILDCARD_MEDIA_TYPES.contains(&essence.as_str()) || essence.ends_with("/*") {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::WILDCARD_REQUEST_MEDIA_TYPE_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} accepts `{}` request bodies. Any content can be sent and it can't be validated, accept explicit media types only", op.label(), media_type), 0, WeightScore::Medium));
+            }
+            if essence == "application/xml" || essence == "text/xml" || essence.ends_with("+xml") {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::XML_REQUEST_BODY_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} accepts XML request bodies (`{}`). XML parsers resolving DTDs and external entities are exposed to XXE, disable them or accept JSON only", op.label(), media_type), 0, WeightScore::Medium));
+            }
+            check_file_uploads(
+                spec,
+                &op,
+                media_type,
+                media,
+                final_security_category_score,
+                &mut fixables,
+            );
+        }
+    }
+
+    fixables
+}
+
+/// Binary properties of a request body (`format: binary`/`byte`) and raw binary bodies
+fn check_file_uploads(
+    spec: &Value,
+    op: &OperationRef,
+    media_type: &str,
+    media: &Value,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let Some(schema) = media.get("schema").map(|schema| resolve_ref(spec, schema)) else {
+        return;
+    };
+    let mut files: Vec<(String, &Value)> = Vec::new();
+    if is_file(schema) {
+        files.push((String::new(), schema));
+    }
+    visit_properties(spec, schema, &mut |path, property| {
+        if is_file(property) {
+            files.push((path.to_string(), property));
+        }
+    });
+
+    for (path, file) in files {
+        let name = if path.is_empty() {
+            format!("The `{}` request body", media_type)
+        } else {
+            format!("File upload `{}` ({})", path, media_type)
+        };
+        if file.get("maxLength").is_none() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::FILE_UPLOAD_WITHOUT_SIZE_LIMIT_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} of {} has no size limit (`maxLength`). Uploads of any size can exhaust disk and memory", name, op.label()), 0, WeightScore::High));
+        }
+        // the content type of a multipart part is restricted through `encoding`
+        let top_level = path.split('.').next().unwrap_or_default();
+        let restricted = media
+            .get("encoding")
+            .and_then(|encoding| encoding.get(top_level))
+            .and_then(|encoding| encoding.get("contentType"))
+            .and_then(Value::as_str)
+            .map(|content_type| !content_type.contains('*'))
+            .unwrap_or(false);
+        if !path.is_empty() && !restricted {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::FILE_UPLOAD_WITHOUT_CONTENT_TYPE_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} of {} doesn't restrict the accepted content types (`encoding.{}.contentType`). Any file type, including executables and HTML, can be uploaded", name, op.label(), top_level), 0, WeightScore::Medium));
+        }
+    }
+}
+
+fn is_file(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("string")
+        && matches!(
+            schema.get("format").and_then(Value::as_str),
+            Some("binary") | Some("byte")
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings(content: Value) -> Vec<String> {
+        let spec = json!({ "paths": { "/documents": { "post": {
+            "requestBody": { "content": content }
+        }}}});
+        check_request_content(&spec, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn wildcard_and_xml_media_types_are_reported() {
+        let found = findings(json!({ "*/*": {}, "application/soap+xml": {} }));
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("accepts `*/*` request bodies"));
+        assert!(found[1].contains("accepts XML request bodies (`application/soap+xml`)"));
+
+        assert!(findings(json!({ "application/json; charset=utf-8": {} })).is_empty());
+    }
+
+    #[test]
+    fn unrestricted_file_uploads_are_reported() {
+        let found = findings(json!({ "multipart/form-data": { "schema": {
+            "type": "object",
+            "properties": { "file": { "type": "string", "format": "binary" } }
+        }}}));
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("File upload `file` (multipart/form-data)"));
+        assert!(found[0].contains("has no size limit"));
+        assert!(found[1].contains("`encoding.file.contentType`"));
+    }
+
+    #[test]
+    fn limited_and_typed_file_uploads_are_accepted() {
+        let found = findings(json!({ "multipart/form-data": {
+            "schema": {
+                "type": "object",
+                "properties": {
+                    "file": { "type": "string", "format": "binary", "maxLength": 1048576 }
+                }
+            },
+            "encoding": { "file": { "contentType": "image/png, image/jpeg" } }
+        }}));
+        assert!(found.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{operations, resolve_ref, visit_properties};
+
+/// Property names (lower case, without `_` and `-`) exposing implementation details
+const LEAKY_PROPERTIES: [&str; 12] = [
+    "stacktrace",
+    "stack",
+    "traceback",
+    "exception",
+    "innerexception",
+    "exceptiontype",
+    "sql",
+    "sqlquery",
+    "sqlstate",
+    "query",
+    "debug",
+    "debuginfo",
+];
+
+/// One schema used by an error response: the name of the component it references or `inline`
+struct ErrorSchema<'a> {
+    label: String,
+    code: &'a str,
+    name: String,
+    schema: &'a Value,
+}
+
+/// Inspect the schemas of 4xx/5xx (and `default`) responses for stack traces and other internal
+/// details, and flag error responses that stray from the API's standard error model.
+pub fn check_error_responses(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let error_schemas = error_schemas(spec);
+
+    let mut inspected: Vec<&str> = Vec::new();
+    for error in &error_schemas {
+        // shared components are reported once, inline schemas for every response
+        if error.name != "inline" {
+            if inspected.contains(&error.name.as_str()) {
+                continue;
+            }
+            inspected.push(&error.name);
+        }
+        let mut leaks: Vec<String> = Vec::new();
+        visit_properties(spec, error.schema, &mut |path, _| {
+            let name = path
+                .rsplit('.')
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+                .replace(['_', '-'], "");
+            if LEAKY_PROPERTIES.contains(&name.as_str()) {
+                leaks.push(format!("`{}`", path));
+            }
+        });
+        if !leaks.is_empty() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::ERROR_DETAILS_LEAKAGE_VALUE,
+            );
+            let schema = if error.name == "inline" {
+                format!(
+                    "The inline schema of the {} response of {}",
+                    error.code, error.label
+                )
+            } else {
+                format!(
+                    "Error schema `{}` (first used by the {} response of {})",
+                    error.name, error.code, error.label
+                )
+            };
+            fixables.push(Fixable::new(format!("{} exposes internal details through {}. Stack traces, exceptions and queries help attackers map the implementation, log them server side instead", schema, leaks.join(", ")), 0, WeightScore::High));
+        }
+    }
+
+    let mut usage: BTreeMap<&str, usize> = BTreeMap::new();
+    for error in &error_schemas {
+        *usage.entry(error.name.as_str()).or_default() += 1;
+    }
+    let standard = usage
+        .iter()
+        .filter(|(name, _)| **name != "inline")
+        .max_by_key(|(_, count)| **count)
+        .map(|(name, _)| *name);
+    if let Some(standard) = standard {
+        let mut reported: Vec<(&str, &str)> = Vec::new();
+        for error in &error_schemas {
+            if error.name != standard && !reported.contains(&(&error.label, error.code)) {
+                reported.push((&error.label, error.code));
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::INCONSISTENT_ERROR_SCHEMA_VALUE,
+                );
+                let used = if error.name == "inline" {
+                    "an inline schema".to_string()
+                } else {
+                    format!("`{}`", error.name)
+                };
+                fixables.push(Fixable::new(format!("The {} response of {} uses {} instead of the standard error model `{}`. Ad hoc error bodies tend to leak framework defaults and details", error.code, error.label, used, standard), 0, WeightScore::Low));
+            }
+        }
+    }
+
+    fixables
+}
+
+/// Schemas of every 4xx, 5xx and `default` response of the document
+fn error_schemas(spec: &Value) -> Vec<ErrorSchema<'_>> {
+    let mut schemas = Vec::new();
+    for op in operations(spec) {
+        let Some(responses) = op.operation.get("responses").and_then(Value::as_object) else {
+            continue;
+        };
+        for (code, response) in responses {
+            if !(code.starts_with('4') || code.starts_with('5') || code == "default") {
+                continue;
+            }
+            let Some(content) = resolve_ref(spec, response)
+                .get("content")
+                .and_then(Value::as_object)
+            else {
+                continue;
+            };
+            for schema in content.values().filter_map(|media| media.get("schema")) {
+                let name = match schema.get("$ref").and_then(Value::as_str) {
+                    Some(reference) => reference.rsplit('/').next().unwrap_or(reference),
+                    None => "inline",
+                };
+                // the same schema is often listed for several media types
+                if schemas.iter().any(|s: &ErrorSchema| {
+                    s.label == op.label() && s.code == code && s.name == name
+                }) {
+                    continue;
+                }
+                schemas.push(ErrorSchema {
+                    label: op.label(),
+                    code,
+                    name: name.to_string(),
+                    schema: resolve_ref(spec, schema),
+                });
+            }
+        }
+    }
+    schemas
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn spec_with_errors(errors: Value, schemas: Value) -> Value {
+        json!({
+            "paths": { "/mangoes": { "get": { "responses": errors } } },
+            "components": { "schemas": schemas }
+        })
+    }
+
+    fn json_body(schema: Value) -> Value {
+        json!({ "content": { "application/json": { "schema": schema } } })
+    }
+
+    fn findings(spec: &Value) -> Vec<String> {
+        check_error_responses(spec, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn leaked_internals_are_reported_once_per_schema() {
+        let spec = spec_with_errors(
+            json!({
+                "400": json_body(json!({ "$ref": "#/components/schemas/Error" })),
+                "500": json_body(json!({ "$ref": "#/components/schemas/Error" }))
+            }),
+            json!({ "Error": { "type": "object", "properties": {
+                "message": { "type": "string" },
+                "stack_trace": { "type": "string" },
+                "cause": { "type": "object", "properties": { "sqlState": { "type": "string" } } }
+            }}}),
+        );
+        let found = findings(&spec);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("Error schema `Error` (first used by the 400 response"));
+        assert!(found[0].contains("`cause.sqlState`, `stack_trace`"));
+    }
+
+    #[test]
+    fn responses_straying_from_the_standard_error_model_are_reported() {
+        let problem = json!({ "type": "object", "properties": { "title": { "type": "string" } } });
+        let spec = spec_with_errors(
+            json!({
+                "400": json_body(json!({ "$ref": "#/components/schemas/Problem" })),
+                "404": json_body(json!({ "$ref": "#/components/schemas/Problem" })),
+                "500": json_body(problem.clone())
+            }),
+            json!({ "Problem": problem }),
+        );
+        let found = findings(&spec);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains(
+            "The 500 response of GET /mangoes uses an inline schema instead of the standard error model `Problem`"
+        ));
+    }
+
+    #[test]
+    fn a_consistent_error_model_without_internals_passes() {
+        let spec = spec_with_errors(
+            json!({
+                "404": json_body(json!({ "$ref": "#/components/schemas/Problem" })),
+                "default": json_body(json!({ "$ref": "#/components/schemas/Problem" }))
+            }),
+            json!({ "Problem": { "type": "object", "properties": {
+                "title": { "type": "string" },
+                "requestId": { "type": "string" }
+            }}}),
+        );
+        assert!(findings(&spec).is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::{EffectiveSecurity, SecurityRequirement};
+use crate::rules::{name_has_word, operations, OperationRef};
+
+/// Words (lower case) of path segments and tags naming privileged functionality
+const PRIVILEGED_KEYWORDS: [&str; 16] = [
+    "admin",
+    "administrator",
+    "administration",
+    "internal",
+    "debug",
+    "manage",
+    "management",
+    "config",
+    "configuration",
+    "actuator",
+    "superuser",
+    "sudo",
+    "backoffice",
+    "staff",
+    "console",
+    "settings",
+];
+
+/// OWASP API5 (broken function level authorization): privileged endpoints that are
+/// unauthenticated or protected like any ordinary one, and destructive operations requiring no
+/// more than reading the same resource.
+pub fn check_function_level_authorization(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let operations = operations(spec);
+
+    let (privileged, ordinary): (Vec<&OperationRef>, Vec<&OperationRef>) = operations
+        .iter()
+        .partition(|op| privileged_keyword(op).is_some());
+    let ordinary_security: Vec<Vec<SecurityRequirement>> = ordinary
+        .iter()
+        .map(|op| normalised(&EffectiveSecurity::of(spec, op.operation)))
+        .filter(|requirements| !requirements.is_empty())
+        .collect();
+
+    for op in privileged {
+        let keyword = privileged_keyword(op).unwrap_or_default();
+        let security = EffectiveSecurity::of(spec, op.operation);
+        if security.is_unauthenticated() || security.allows_anonymous() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNAUTHENTICATED_PRIVILEGED_OPERATION_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Privileged operation {} (`{}`) can be called without authentication. Administrative functions must require authentication and an admin role or scope", op.label(), keyword), 0, WeightScore::Critical));
+        } else if ordinary_security.contains(&normalised(&security)) {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::PRIVILEGED_OPERATION_WITH_ORDINARY_SECURITY_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Privileged operation {} (`{}`) requires the same security as ordinary endpoints ({}). Any regular user may be able to call it, require a dedicated admin scope or scheme", op.label(), keyword, security.describe()), 0, WeightScore::High));
+        }
+    }
+
+    for op in operations
+        .iter()
+        .filter(|op| op.method == "delete" || op.method == "put")
+    {
+        let Some(get) = operations
+            .iter()
+            .find(|other| other.path == op.path && other.method == "get")
+        else {
+            continue;
+        };
+        let read = EffectiveSecurity::of(spec, get.operation);
+        let write = EffectiveSecurity::of(spec, op.operation);
+        // unauthenticated operations are already reported by the security requirement checks
+        if read.is_unauthenticated() || write.is_unauthenticated() {
+            continue;
+        }
+        if requires_no_more_than(&write, &read) {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::WRITE_REQUIRES_NO_MORE_THAN_READ_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} requires no more privileges than {} ({}). Anyone allowed to read the resource can also modify or delete it", op.label(), get.label(), write.describe()), 0, WeightScore::Medium));
+        }
+    }
+
+    fixables
+}
+
+/// The first privileged keyword that is a word of the path or of the tags of the operation
+fn privileged_keyword(op: &OperationRef) -> Option<&'static str> {
+    let tags: Vec<&str> = op
+        .operation
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    PRIVILEGED_KEYWORDS.into_iter().find(|keyword| {
+        op.path
+            .split('/')
+            .chain(tags.iter().copied())
+            .any(|name| name_has_word(name, &[keyword]))
+    })
+}
+
+/// Requirements with schemes, scopes and alternatives sorted so they can be compared
+fn normalised(security: &EffectiveSecurity) -> Vec<SecurityRequirement> {
+    let mut requirements: Vec<SecurityRequirement> = security
+        .requirements
+        .iter()
+        .map(|requirement| {
+            let mut requirement: SecurityRequirement = requirement
+                .iter()
+                .map(|(name, scopes)| {
+                    let mut scopes = scopes.clone();
+                    scopes.sort();
+                    (name.clone(), scopes)
+                })
+                .collect();
+            requirement.sort();
+            requirement
+        })
+        .collect();
+    requirements.sort();
+    requirements
+}
+
+/// Whoever can read can also write: every way of satisfying `read` holds the credentials of
+/// some way of satisfying `write`
+fn requires_no_more_than(write: &EffectiveSecurity, read: &EffectiveSecurity) -> bool {
+    read.requirements.iter().all(|read_requirement| {
+        write
+            .requirements
+            .iter()
+            .any(|write_requirement| is_covered_by(write_requirement, read_requirement))
+    })
+}
+
+/// The credentials `credentials` also satisfy `requirement`: each of its schemes is present with
+/// at least its scopes
+fn is_covered_by(requirement: &SecurityRequirement, credentials: &SecurityRequirement) -> bool {
+    requirement.iter().all(|(scheme, scopes)| {
+        credentials.iter().any(|(held_scheme, held_scopes)| {
+            held_scheme == scheme && scopes.iter().all(|scope| held_scopes.contains(scope))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn security(requirements: Value) -> EffectiveSecurity {
+        EffectiveSecurity::of(&json!({}), &json!({ "security": requirements }))
+    }
+
+    #[test]
+    fn write_with_fewer_alternatives_than_read_is_stricter() {
+        // a reader holding only the API key can't delete
+        let read = security(json!([{ "bearer": [] }, { "apiKey": [] }]));
+        let write = security(json!([{ "bearer": [] }]));
+        assert!(!requires_no_more_than(&write, &read));
+    }
+
+    #[test]
+    fn write_accepting_an_extra_alternative_requires_no_more() {
+        let read = security(json!([{ "bearer": [] }]));
+        let write = security(json!([{ "bearer": [] }, { "basic": [] }]));
+        assert!(requires_no_more_than(&write, &read));
+    }
+
+    #[test]
+    fn scopes_are_compared() {
+        let read = security(json!([{ "oauth": ["items:read"] }]));
+        let write = security(json!([{ "oauth": ["items:write"] }]));
+        assert!(!requires_no_more_than(&write, &read));
+
+        let read = security(json!([{ "oauth": ["items:read", "items:write"] }]));
+        assert!(requires_no_more_than(&write, &read));
+    }
+
+    fn privileged_keyword_of(path: &str, tags: Value) -> Option<&'static str> {
+        let operation = json!({ "tags": tags });
+        let op = OperationRef {
+            path,
+            method: "get",
+            path_item: &Value::Null,
+            operation: &operation,
+        };
+        privileged_keyword(&op)
+    }
+
+    #[test]
+    fn privileged_words_of_paths_and_tags_are_recognised() {
+        assert_eq!(
+            privileged_keyword_of("/admin/users", json!([])),
+            Some("admin")
+        );
+        assert_eq!(
+            privileged_keyword_of("/userSettings", json!([])),
+            Some("settings")
+        );
+        assert_eq!(
+            privileged_keyword_of("/reports", json!(["Staff"])),
+            Some("staff")
+        );
+    }
+
+    #[test]
+    fn words_merely_containing_privileged_keywords_are_not_privileged() {
+        for path in [
+            "/badminton",
+            "/staffing-agencies",
+            "/resettings",
+            "/debugger-free",
+        ] {
+            assert_eq!(privileged_keyword_of(path, json!([])), None, "{}", path);
+        }
+        assert_eq!(privileged_keyword_of("/courts", json!(["Badminton"])), None);
+    }
+}
@@ -0,0 +1,280 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::EffectiveSecurity;
+use crate::rules::{content_schemas, operations, resolve_ref, visit_properties, OperationRef};
+
+/// Headers every successful response must document unless configured otherwise
+pub const DEFAULT_REQUIRED_HEADERS: [&str; 2] =
+    ["Strict-Transport-Security", "X-Content-Type-Options"];
+/// Response property names (lower case, without `_` and `-`) that carry credentials
+const TOKEN_PROPERTIES: [&str; 8] = [
+    "token",
+    "accesstoken",
+    "refreshtoken",
+    "idtoken",
+    "sessiontoken",
+    "sessionid",
+    "apikey",
+    "clientsecret",
+];
+
+/// The response headers the security header checks require.
+#[derive(Debug, Clone)]
+pub struct HeaderPolicy {
+    /// Headers every successful response has to document, compared case insensitively
+    pub required_headers: Vec<String>,
+}
+
+impl HeaderPolicy {
+    pub fn new(required_headers: Vec<String>) -> Self {
+        Self { required_headers }
+    }
+}
+
+impl Default for HeaderPolicy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_REQUIRED_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// Check the documented headers of successful responses against the header policy, require
+/// `Cache-Control: no-store` on responses carrying tokens and flag wildcard CORS origins on
+/// authenticated operations.
+pub fn check_security_headers(
+    spec: &Value,
+    policy: &HeaderPolicy,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let operations = operations(spec);
+
+    for required in &policy.required_headers {
+        let documented_anywhere = operations.iter().any(|op| {
+            success_responses(spec, op)
+                .iter()
+                .any(|(_, response)| find_header(spec, response, required).is_some())
+        });
+        if !operations.is_empty() && !documented_anywhere {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::MISSING_SECURITY_HEADER_VALUE,
+            );
+            fixables.push(Fixable::new(format!("No response documents the `{}` header required by the header policy. Document it on every successful response so its presence can be verified", required), 0, WeightScore::Medium));
+            continue;
+        }
+        for op in &operations {
+            for (code, response) in success_responses(spec, op) {
+                if find_header(spec, response, required).is_none() {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::MISSING_SECURITY_HEADER_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("The {} response of {} doesn't document the `{}` header required by the header policy", code, op.label(), required), 0, WeightScore::Low));
+                }
+            }
+        }
+    }
+
+    for op in &operations {
+        for (code, response) in success_responses(spec, op) {
+            if carries_token(spec, response) {
+                let no_store = find_header(spec, response, "Cache-Control")
+                    .map(|header| {
+                        documented_values(spec, header)
+                            .iter()
+                            .any(|value| value.to_lowercase().contains("no-store"))
+                    })
+                    .unwrap_or(false);
+                if !no_store {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::CACHEABLE_TOKEN_RESPONSE_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("The {} response of {} carries a token but doesn't document `Cache-Control: no-store`. Browsers and proxies may cache the credential", code, op.label()), 0, WeightScore::High));
+                }
+            }
+        }
+
+        if EffectiveSecurity::of(spec, op.operation).is_unauthenticated() {
+            continue;
+        }
+        for (code, response) in all_responses(spec, op) {
+            let wildcard = find_header(spec, response, "Access-Control-Allow-Origin")
+                .map(|header| {
+                    documented_values(spec, header)
+                        .iter()
+                        .any(|value| value.trim() == "*")
+                })
+                .unwrap_or(false);
+            if wildcard {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::WILDCARD_CORS_ON_AUTHENTICATED_OPERATION_VALUE,
+                );
+                fixables.push(Fixable::new(format!("The {} response of authenticated operation {} documents `Access-Control-Allow-Origin: *`. Any website can read its responses from a browser, allow the trusted origins only", code, op.label()), 0, WeightScore::High));
+            }
+        }
+    }
+
+    fixables
+}
+
+/// Resolved responses of an operation along with their status code
+fn all_responses<'a>(spec: &'a Value, op: &OperationRef<'a>) -> Vec<(&'a str, &'a Value)> {
+    op.operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .map(|responses| {
+            responses
+                .iter()
+                .map(|(code, response)| (code.as_str(), resolve_ref(spec, response)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn success_responses<'a>(spec: &'a Value, op: &OperationRef<'a>) -> Vec<(&'a str, &'a Value)> {
+    all_responses(spec, op)
+        .into_iter()
+        .filter(|(code, _)| code.starts_with('2'))
+        .collect()
+}
+
+/// Header `name` of a response, header names are case insensitive
+fn find_header<'a>(spec: &'a Value, response: &'a Value, name: &str) -> Option<&'a Value> {
+    response
+        .get("headers")
+        .and_then(Value::as_object)?
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, header)| resolve_ref(spec, header))
+}
+
+/// Values a header object documents through `example`, `examples` and its schema's
+/// `default`, `example`, `enum` and `const`
+fn documented_values(spec: &Value, header: &Value) -> Vec<String> {
+    let mut values: Vec<&Value> = Vec::new();
+    values.extend(header.get("example"));
+    if let Some(examples) = header.get("examples").and_then(Value::as_object) {
+        values.extend(
+            examples
+                .values()
+                .filter_map(|example| resolve_ref(spec, example).get("value")),
+        );
+    }
+    if let Some(schema) = header.get("schema").map(|schema| resolve_ref(spec, schema)) {
+        for keyword in ["default", "example", "const"] {
+            values.extend(schema.get(keyword));
+        }
+        values.extend(
+            schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten(),
+        );
+    }
+    values
+        .into_iter()
+        .filter_map(|value| value.as_str().map(String::from))
+        .collect()
+}
+
+fn carries_token(spec: &Value, response: &Value) -> bool {
+    let mut found = false;
+    for schema in content_schemas(spec, response) {
+        visit_properties(spec, schema, &mut |path, _| {
+            let name = path
+                .rsplit('.')
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+                .replace(['_', '-'], "");
+            found |= TOKEN_PROPERTIES.contains(&name.as_str());
+        });
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings(spec: &Value, policy: &HeaderPolicy) -> Vec<String> {
+        check_security_headers(spec, policy, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    fn spec_with_response(security: Value, response: Value) -> Value {
+        json!({
+            "paths": { "/session": { "post": {
+                "security": security,
+                "responses": { "200": response }
+            }}}
+        })
+    }
+
+    #[test]
+    fn required_headers_are_matched_case_insensitively() {
+        let spec = spec_with_response(
+            json!([]),
+            json!({ "headers": { "strict-transport-security": {}, "X-CONTENT-TYPE-OPTIONS": {} } }),
+        );
+        assert!(findings(&spec, &HeaderPolicy::default()).is_empty());
+
+        let policy = HeaderPolicy::new(vec!["Content-Security-Policy".to_string()]);
+        let found = findings(&spec, &policy);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("No response documents the `Content-Security-Policy` header"));
+    }
+
+    #[test]
+    fn token_responses_need_no_store() {
+        let body = json!({ "application/json": { "schema": {
+            "type": "object",
+            "properties": { "access_token": { "type": "string" } }
+        }}});
+        let policy = HeaderPolicy::new(vec![]);
+
+        let spec = spec_with_response(json!([]), json!({ "content": body }));
+        let found = findings(&spec, &policy);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("carries a token"));
+
+        let spec = spec_with_response(
+            json!([]),
+            json!({
+                "content": body,
+                "headers": { "Cache-Control": { "schema": { "type": "string", "example": "no-store" } } }
+            }),
+        );
+        assert!(findings(&spec, &policy).is_empty());
+    }
+
+    #[test]
+    fn wildcard_cors_is_reported_on_authenticated_operations_only() {
+        let response = json!({ "headers": { "Access-Control-Allow-Origin": {
+            "schema": { "type": "string", "enum": ["*"] }
+        }}});
+        let policy = HeaderPolicy::new(vec![]);
+
+        let spec = spec_with_response(json!([{ "Bearer": [] }]), response.clone());
+        let found = findings(&spec, &policy);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("`Access-Control-Allow-Origin: *`"));
+
+        let spec = spec_with_response(json!([]), response);
+        assert!(findings(&spec, &policy).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::EffectiveSecurity;
+use crate::rules::{operations, resolve_ref, OperationRef};
+
+/// Path segments naming an API version, e.g. `v1`, `v2.1`
+const VERSION_SEGMENT_PATTERN: &str = r"(?i)^v\d+(\.\d+)*$";
+/// Extensions documenting when a deprecated operation goes away
+const SUNSET_EXTENSIONS: [&str; 4] = [
+    "x-sunset",
+    "x-sunset-date",
+    "x-deprecation-date",
+    "x-removal-date",
+];
+
+/// OWASP API9 (improper inventory management): deprecated operations, co-existing API versions
+/// and missing API metadata. Builds the endpoint inventory.
+pub fn check_inventory(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut inventory = ReportTable::new(
+        "Endpoint inventory",
+        &["Endpoint", "Method", "Tags", "Auth", "Deprecated"],
+    );
+    let version_segment = Regex::new(VERSION_SEGMENT_PATTERN).unwrap();
+    let mut versions: BTreeMap<String, usize> = BTreeMap::new();
+
+    for op in operations(spec) {
+        let security = EffectiveSecurity::of(spec, op.operation);
+        let deprecated = op.operation.get("deprecated").and_then(Value::as_bool) == Some(true);
+        let sunset = sunset_of(spec, &op);
+        let tags: Vec<&str> = op
+            .operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        inventory.add_row(vec![
+            op.path.to_string(),
+            op.method.to_uppercase(),
+            tags.join(", "),
+            security.describe(),
+            match (deprecated, &sunset) {
+                (true, Some(sunset)) => format!("yes, sunset {}", sunset),
+                (true, None) => "yes".to_string(),
+                _ => "no".to_string(),
+            },
+        ]);
+
+        if let Some(version) = op.path.split('/').find(|s| version_segment.is_match(s)) {
+            *versions.entry(version.to_lowercase()).or_default() += 1;
+        }
+
+        if !deprecated {
+            continue;
+        }
+        if security.is_unauthenticated() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNAUTHENTICATED_DEPRECATED_OPERATION_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Deprecated operation {} is still reachable without authentication. Forgotten endpoints are rarely patched, secure or retire it", op.label()), 0, WeightScore::High));
+        }
+        if sunset.is_none() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::DEPRECATED_OPERATION_WITHOUT_SUNSET_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Deprecated operation {} has no sunset date (`x-sunset` extension or `Sunset` response header). Plan and document when it will be removed", op.label()), 0, WeightScore::Low));
+        }
+    }
+
+    if versions.len() > 1 {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MULTIPLE_API_VERSIONS_VALUE,
+        );
+        let versions: Vec<String> = versions
+            .iter()
+            .map(|(version, count)| format!("{} ({} operation(s))", version, count))
+            .collect();
+        fixables.push(Fixable::new(format!("Several API versions co-exist in the paths: {}. Older versions tend to lack the latest security fixes, retire them or keep them on the same footing", versions.join(", ")), 0, WeightScore::Medium));
+    }
+
+    let info = spec.get("info");
+    let version = info
+        .and_then(|info| info.get("version"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if version.trim().is_empty() {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MISSING_API_METADATA_VALUE,
+        );
+        fixables.push(Fixable::new("`info.version` is missing. Without a version deployed APIs can't be tracked against their specification", 0, WeightScore::Medium));
+    }
+    let contact = info.and_then(|info| info.get("contact"));
+    let reachable = contact
+        .map(|contact| contact.get("email").is_some() || contact.get("url").is_some())
+        .unwrap_or(false);
+    if !reachable {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MISSING_API_METADATA_VALUE,
+        );
+        fixables.push(Fixable::new("`info.contact` has no email or url. Document the team owning the API so vulnerabilities can be reported and inventory kept up to date", 0, WeightScore::Low));
+    }
+
+    tables.push(inventory);
+    fixables
+}
+
+/// Sunset date of an operation, from an extension or an example of a documented `Sunset` header
+fn sunset_of(spec: &Value, op: &OperationRef) -> Option<String> {
+    for extension in SUNSET_EXTENSIONS {
+        if let Some(value) = op.operation.get(extension) {
+            return Some(match value {
+                Value::String(date) => date.clone(),
+                other => other.to_string(),
+            });
+        }
+    }
+    let responses = op.operation.get("responses").and_then(Value::as_object)?;
+    responses
+        .values()
+        .filter_map(|response| resolve_ref(spec, response).get("headers"))
+        .filter_map(Value::as_object)
+        .flat_map(|headers| headers.iter())
+        .find(|(name, _)| name.eq_ignore_ascii_case("sunset"))
+        .map(|(_, header)| {
+            let header = resolve_ref(spec, header);
+            header
+                .get("example")
+                .or_else(|| header.pointer("/schema/example"))
+                .and_then(Value::as_str)
+                .unwrap_or("header documented")
+                .to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings(spec: &Value, tables: &mut Vec<ReportTable>) -> Vec<String> {
+        check_inventory(spec, &mut 30, tables)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn forgotten_versions_and_missing_metadata_are_reported() {
+        let spec = json!({
+            "info": { "title": "Mangoes" },
+            "paths": {
+                "/v1/mangoes": { "get": { "deprecated": true } },
+                "/v2/mangoes": { "get": { "security": [{ "Bearer": [] }] } }
+            }
+        });
+        let mut tables = vec![];
+        let found = findings(&spec, &mut tables);
+        assert_eq!(found.len(), 5);
+        assert!(found[0].contains("Deprecated operation GET /v1/mangoes is still reachable"));
+        assert!(found[1].contains("has no sunset date"));
+        assert!(found[2].contains("v1 (1 operation(s)), v2 (1 operation(s))"));
+        assert!(found[3].contains("`info.version` is missing"));
+        assert!(found[4].contains("`info.contact` has no email or url"));
+        assert_eq!(tables[0].rows.len(), 2);
+    }
+
+    #[test]
+    fn documented_deprecations_of_a_single_version_are_only_inventoried() {
+        let spec = json!({
+            "info": { "version": "2.1.0", "contact": { "email": "api@example.com" } },
+            "security": [{ "Bearer": [] }],
+            "paths": {
+                "/v2/mangoes": { "get": {
+                    "deprecated": true,
+                    "responses": { "200": { "headers": {
+                        "Sunset": { "schema": { "type": "string", "example": "2027-01-01" } }
+                    }}}
+                }},
+                "/v2/mangoes/{id}": { "get": {} }
+            }
+        });
+        let mut tables = vec![];
+        assert!(findings(&spec, &mut tables).is_empty());
+        assert_eq!(tables[0].rows[0][4], "yes, sunset 2027-01-01");
+    }
+}
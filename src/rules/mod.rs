@@ -0,0 +1,284 @@
+//! Security rules run against an OpenAPI specification.
+//!
+//! The rules walk the raw JSON document rather than `OApiDocument`: oapi cannot
+//! represent root level `security` requirements and leaves `$ref`s unresolved,
+//! both of which most of these checks depend on.
+//!
+//! Rules for WSDL (SOAP) service descriptions live in [`wsdl`].
+
+pub mod auth;
+pub mod bola;
+pub mod content;
+pub mod error_responses;
+pub mod function_level;
+pub mod headers;
+pub mod inventory;
+pub mod oauth;
+pub mod rate_limit;
+pub mod responses;
+pub mod schemes;
+pub mod sensitive_data;
+pub mod servers;
+pub mod ssrf;
+pub mod wsdl;
+
+use serde_json::Value;
+
+/// Every HTTP method an OpenAPI path item can declare an operation for.
+pub const HTTP_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// HTTP methods that change server side state.
+pub const STATE_CHANGING_METHODS: [&str; 4] = ["post", "put", "patch", "delete"];
+
+/// A single operation (endpoint + HTTP method) of an OpenAPI document.
+#[derive(Debug, Clone, Copy)]
+pub struct OperationRef<'a> {
+    pub path: &'a str,
+    pub method: &'a str,
+    pub path_item: &'a Value,
+    pub operation: &'a Value,
+}
+
+impl<'a> OperationRef<'a> {
+    pub fn is_state_changing(&self) -> bool {
+        STATE_CHANGING_METHODS.contains(&self.method)
+    }
+    /// Endpoint label used in fixables and report tables, e.g. `POST /mangoes`
+    pub fn label(&self) -> String {
+        format!("{} {}", self.method.to_uppercase(), self.path)
+    }
+    /// Whether the operation documents a response for the status `code`, either exactly
+    /// or through a range like `4XX`
+    pub fn documents_response(&self, code: &str) -> bool {
+        let Some(responses) = self.operation.get("responses").and_then(Value::as_object) else {
+            return false;
+        };
+        let range = code.get(..1).map(|class| format!("{}XX", class));
+        responses.keys().any(|key| {
+            key == code
+                || range
+                    .as_ref()
+                    .is_some_and(|range| key.eq_ignore_ascii_case(range))
+        })
+    }
+    /// Whether the operation documents any response whose status starts with `class`, e.g. `5`
+    pub fn documents_response_class(&self, class: char) -> bool {
+        self.operation
+            .get("responses")
+            .and_then(Value::as_object)
+            .map(|responses| responses.keys().any(|key| key.starts_with(class)))
+            .unwrap_or(false)
+    }
+    /// Resolved parameters of the operation, path level ones included unless the
+    /// operation overrides them (same `name` and `in`)
+    pub fn parameters(&self, spec: &'a Value) -> Vec<&'a Value> {
+        let declared = |item: &'a Value| -> Vec<&'a Value> {
+            item.get("parameters")
+                .and_then(Value::as_array)
+                .map(|parameters| parameters.iter().map(|p| resolve_ref(spec, p)).collect())
+                .unwrap_or_default()
+        };
+        let key = |p: &Value| (p.get("name").cloned(), p.get("in").cloned());
+        let mut parameters = declared(self.operation);
+        for inherited in declared(self.path_item) {
+            if !parameters.iter().any(|p| key(p) == key(inherited)) {
+                parameters.push(inherited);
+            }
+        }
+        parameters
+    }
+}
+
+/// Collect every operation declared under `paths`, sorted by path then method.
+pub fn operations(spec: &Value) -> Vec<OperationRef<'_>> {
+    let mut operations = Vec::new();
+    if let Some(paths) = spec.get("paths").and_then(Value::as_object) {
+        let mut urls: Vec<&String> = paths.keys().collect();
+        urls.sort();
+        for url in urls {
+            let path_item = resolve_ref(spec, &paths[url]);
+            for method in HTTP_METHODS {
+                if let Some(operation) = path_item.get(method) {
+                    operations.push(OperationRef {
+                        path: url,
+                        method,
+                        path_item,
+                        operation,
+                    });
+                }
+            }
+        }
+    }
+    operations
+}
+
+/// Follow a local `$ref` (`#/components/...`) to the value it points at.
+/// <br>Values that are not references, or references that can't be resolved, are returned as is.
+pub fn resolve_ref<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    // bounded so that self referencing `$ref`s can't loop forever
+    for _ in 0..16 {
+        match current.get("$ref").and_then(Value::as_str) {
+            Some(reference) if reference.starts_with('#') => match spec.pointer(&reference[1..]) {
+                Some(target) => current = target,
+                None => break,
+            },
+            _ => break,
+        }
+    }
+    current
+}
+
+/// Resolved schemas of every media type under the `content` of a response or request body
+pub fn content_schemas<'a>(spec: &'a Value, holder: &'a Value) -> Vec<&'a Value> {
+    resolve_ref(spec, holder)
+        .get("content")
+        .and_then(Value::as_object)
+        .map(|content| {
+            content
+                .values()
+                .filter_map(|media_type| media_type.get("schema"))
+                .map(|schema| resolve_ref(spec, schema))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Call `visit` with the dotted path (e.g. `owner.avatarUrl`) and resolved schema of every
+/// property nested in `schema`, through `items`, `allOf`/`oneOf`/`anyOf` and `$ref`s.
+/// <br>Schemas can be recursive, so the walk stops at a fixed depth.
+pub fn visit_properties<'a>(
+    spec: &'a Value,
+    schema: &'a Value,
+    visit: &mut dyn FnMut(&str, &'a Value),
+) {
+    fn walk<'a>(
+        spec: &'a Value,
+        schema: &'a Value,
+        prefix: &str,
+        depth: usize,
+        visit: &mut dyn FnMut(&str, &'a Value),
+    ) {
+        if depth > 8 {
+            return;
+        }
+        let schema = resolve_ref(spec, schema);
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                let path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                visit(&path, resolve_ref(spec, property));
+                walk(spec, property, &path, depth + 1, visit);
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            walk(spec, items, prefix, depth + 1, visit);
+        }
+        for combinator in ["allOf", "oneOf", "anyOf"] {
+            for part in schema
+                .get(combinator)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                walk(spec, part, prefix, depth + 1, visit);
+            }
+        }
+    }
+    walk(spec, schema, "", 0, visit);
+}
+
+/// Upper case the first letter of labels such as "element `X`" or "query" that start a sentence
+pub fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Split an identifier into lower case words at `_`, `-`, `.`, spaces and camelCase
+/// boundaries: `redirect_uri`, `redirectURI` and `Redirect-Uri` all give `redirect`, `uri`.
+pub fn name_tokens(name: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for part in name.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut current = String::new();
+        for (index, &c) in chars.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(index + 1);
+            // `aB` starts a word at B, and so does `ABc` (the end of an acronym)
+            let boundary = c.is_uppercase()
+                && (previous.is_some_and(|p| p.is_lowercase() || p.is_numeric())
+                    || (previous.is_some_and(char::is_uppercase)
+                        && next.is_some_and(|n| n.is_lowercase())));
+            if boundary && !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current.extend(c.to_lowercase());
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+    tokens
+}
+
+/// One of `words` (lower case, without separators) is made of whole words of `name`, e.g.
+/// `webhook` in `webHookTarget` but not `feed` in `feedback`. Plurals match too.
+pub fn name_has_word(name: &str, words: &[&str]) -> bool {
+    let tokens = name_tokens(name);
+    (0..tokens.len()).any(|start| {
+        let mut joined = String::new();
+        tokens[start..].iter().any(|token| {
+            joined.push_str(token);
+            words
+                .iter()
+                .any(|word| joined == *word || joined.strip_suffix('s') == Some(*word))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn responses_are_documented_by_code_or_range() {
+        let spec = json!({ "paths": { "/mangoes": { "get": {
+            "responses": { "200": {}, "4xx": {} }
+        }}}});
+        let op = &operations(&spec)[0];
+        assert!(op.documents_response("200"));
+        assert!(op.documents_response("404"));
+        assert!(!op.documents_response("500"));
+        assert!(!op.documents_response(""));
+        assert!(!op.documents_response("é"));
+    }
+
+    #[test]
+    fn name_tokens_split_separators_and_camel_case() {
+        assert_eq!(name_tokens("redirect_uri"), ["redirect", "uri"]);
+        assert_eq!(name_tokens("redirectURI"), ["redirect", "uri"]);
+        assert_eq!(name_tokens("Redirect-Uri"), ["redirect", "uri"]);
+        assert_eq!(name_tokens("XMLHttpRequest"), ["xml", "http", "request"]);
+        assert_eq!(name_tokens("owner.avatarUrl2"), ["owner", "avatar", "url2"]);
+    }
+
+    #[test]
+    fn name_has_word_matches_whole_words_only() {
+        assert!(name_has_word("callbackUrl", &["url"]));
+        assert!(name_has_word("web_hook", &["webhook"]));
+        assert!(name_has_word("imageURLs", &["url"]));
+        assert!(!name_has_word("securityCode", &["uri"]));
+        assert!(!name_has_word("feedback", &["feed"]));
+        assert!(!name_has_word("curiosity", &["uri"]));
+    }
+}
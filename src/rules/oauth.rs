@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, SecurityScheme, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::EffectiveSecurity;
+use crate::rules::schemes::{declared_requirements, security_schemes};
+use crate::rules::{name_has_word, operations};
+
+/// Scope name words that only grant read access
+const READ_SCOPE_HINTS: [&str; 5] = ["read", "readonly", "view", "list", "get"];
+/// Scope name words that grant write access, they win over read hints (`read_write`)
+const WRITE_SCOPE_HINTS: [&str; 8] = [
+    "write", "admin", "manage", "create", "update", "delete", "edit", "modify",
+];
+
+/// Analyse OAuth2 and OpenID Connect schemes: deprecated flows, plain HTTP endpoints and the
+/// scopes operations require.
+pub fn check_oauth_flows(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    // scheme errors are already reported by the scheme hygiene checks
+    let schemes: BTreeMap<String, SecurityScheme> = security_schemes(spec, &mut Vec::new())
+        .into_iter()
+        .filter_map(|(name, scheme)| scheme.map(|scheme| (name, scheme)))
+        .collect();
+
+    for (name, scheme) in &schemes {
+        match scheme.type_.as_str() {
+            "oauth2" => {
+                check_oauth2_scheme(name, scheme, final_security_category_score, &mut fixables)
+            }
+            "openIdConnect" => {
+                let url = scheme.open_id_connect_url.as_deref().unwrap_or_default();
+                if !is_https(url) {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::INSECURE_OAUTH_URL_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("OpenID Connect scheme `{}` has a non HTTPS `openIdConnectUrl` ({}). Discovery documents fetched over plain HTTP can be tampered with", name, url), 0, WeightScore::Critical));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let oauth_scopes: BTreeMap<&str, Vec<&str>> = schemes
+        .iter()
+        .filter(|(_, scheme)| scheme.type_ == "oauth2")
+        .map(|(name, scheme)| (name.as_str(), declared_scopes(scheme)))
+        .collect();
+    let declared = declared_requirements(spec);
+
+    for (scheme_name, scopes) in &oauth_scopes {
+        for scope in scopes {
+            let used = declared.iter().any(|(_, requirement)| {
+                requirement.iter().any(|(name, required)| {
+                    name == scheme_name && required.iter().any(|r| r == scope)
+                })
+            });
+            if !used {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::UNUSED_OAUTH_SCOPE_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Scope `{}` of OAuth2 scheme `{}` is defined but never required by any operation", scope, scheme_name), 0, WeightScore::Low));
+            }
+        }
+    }
+
+    let mut reported: Vec<(&str, &str, &str)> = Vec::new();
+    for (location, requirement) in &declared {
+        for (scheme_name, required) in requirement {
+            let Some(scopes) = oauth_scopes.get(scheme_name.as_str()) else {
+                continue;
+            };
+            for scope in required {
+                if !scopes.contains(&scope.as_str())
+                    && !reported.contains(&(location, scheme_name, scope))
+                {
+                    reported.push((location, scheme_name, scope));
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::UNDECLARED_OAUTH_SCOPE_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("Security requirement of {} requires scope `{}` which is not declared by any flow of OAuth2 scheme `{}`", location, scope, scheme_name), 0, WeightScore::High));
+                }
+            }
+        }
+    }
+
+    for op in operations(spec).iter().filter(|op| op.is_state_changing()) {
+        let security = EffectiveSecurity::of(spec, op.operation);
+        let read_only = security.requirements.iter().any(|requirement| {
+            let scopes: Vec<&String> = requirement
+                .iter()
+                .filter(|(name, _)| oauth_scopes.contains_key(name.as_str()))
+                .flat_map(|(_, scopes)| scopes)
+                .collect();
+            !scopes.is_empty() && scopes.iter().all(|scope| is_read_scope(scope))
+        });
+        if read_only {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::READ_SCOPE_ON_STATE_CHANGING_OPERATION_VALUE,
+            );
+            fixables.push(Fixable::new(format!("State changing operation {} can be called with read-only scopes ({}). Require a write scope for it", op.label(), security.describe()), 0, WeightScore::Medium));
+        }
+    }
+
+    fixables
+}
+
+fn check_oauth2_scheme(
+    name: &str,
+    scheme: &SecurityScheme,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let Some(flows) = &scheme.flows else {
+        return;
+    };
+    for (flow_name, flow) in flows.declared() {
+        if flow_name == "implicit" || flow_name == "password" {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::DEPRECATED_OAUTH_FLOW_VALUE,
+            );
+            let reason = if flow_name == "implicit" {
+                "it returns access tokens in the redirect URL where they can leak, use the authorization code flow with PKCE instead"
+            } else {
+                "the client handles the user's credentials directly, use the authorization code flow instead"
+            };
+            fixables.push(Fixable::new(
+                format!(
+                    "OAuth2 scheme `{}` uses the deprecated `{}` flow, {}",
+                    name, flow_name, reason
+                ),
+                0,
+                WeightScore::High,
+            ));
+        }
+        let urls = [
+            ("authorizationUrl", &flow.authorization_url),
+            ("tokenUrl", &flow.token_url),
+            ("refreshUrl", &flow.refresh_url),
+        ];
+        for (field, url) in urls {
+            if let Some(url) = url {
+                if !is_https(url) {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::INSECURE_OAUTH_URL_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("`{}` of the `{}` flow of OAuth2 scheme `{}` is not HTTPS ({}). Tokens and authorization codes would travel in plain text", field, flow_name, name, url), 0, WeightScore::Critical));
+                }
+            }
+        }
+    }
+}
+
+fn declared_scopes(scheme: &SecurityScheme) -> Vec<&str> {
+    let mut scopes: Vec<&str> = Vec::new();
+    if let Some(flows) = &scheme.flows {
+        for (_, flow) in flows.declared() {
+            for scope in flow.scopes.keys() {
+                if !scopes.contains(&scope.as_str()) {
+                    scopes.push(scope);
+                }
+            }
+        }
+    }
+    scopes
+}
+
+/// Relative URLs are resolved against the (already checked) server URL, so only absolute ones are judged
+fn is_https(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    url.starts_with("https://") || !url.contains("://")
+}
+
+/// Scopes are compared word by word (`read:items`, `items.view`), so `target` or `reviewer` don't
+/// pass for read scopes
+fn is_read_scope(scope: &str) -> bool {
+    name_has_word(scope, &READ_SCOPE_HINTS) && !name_has_word(scope, &WRITE_SCOPE_HINTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_scopes_are_recognised_by_their_words() {
+        assert!(is_read_scope("read:items"));
+        assert!(is_read_scope("items.view"));
+        assert!(is_read_scope("orders-list"));
+        assert!(is_read_scope("https://example.com/auth/drive.readonly"));
+        assert!(is_read_scope("readInvoices"));
+    }
+
+    #[test]
+    fn write_scopes_and_lookalike_words_are_not_read_scopes() {
+        assert!(!is_read_scope("read_write"));
+        assert!(!is_read_scope("budget:write"));
+        assert!(!is_read_scope("budget"));
+        assert!(!is_read_scope("target"));
+        assert!(!is_read_scope("reviewer"));
+        assert!(!is_read_scope("items:admin"));
+    }
+}
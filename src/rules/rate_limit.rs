@@ -0,0 +1,242 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{content_schemas, operations, resolve_ref, OperationRef};
+
+/// Response headers (lower case) that tell clients about their rate limit
+const RATE_LIMIT_HEADERS: [&str; 9] = [
+    "ratelimit",
+    "ratelimit-policy",
+    "ratelimit-limit",
+    "ratelimit-remaining",
+    "ratelimit-reset",
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "retry-after",
+];
+/// Query parameter names (lower case) used to page through or bound a collection
+const PAGINATION_PARAMETERS: [&str; 16] = [
+    "limit",
+    "size",
+    "pagesize",
+    "page_size",
+    "per_page",
+    "perpage",
+    "page",
+    "pagenumber",
+    "page_number",
+    "offset",
+    "cursor",
+    "after",
+    "top",
+    "$top",
+    "skip",
+    "first",
+];
+/// Pagination parameters that bound the page size and so should declare a `maximum`
+const PAGE_SIZE_PARAMETERS: [&str; 8] = [
+    "limit",
+    "size",
+    "pagesize",
+    "page_size",
+    "per_page",
+    "perpage",
+    "top",
+    "$top",
+];
+/// Last path segments that name a whole collection, e.g. `/mangoes/all`
+const COLLECTION_SEGMENTS: [&str; 4] = ["all", "list", "search", "export"];
+
+/// OWASP API4 (unrestricted resource consumption): look for documented rate limiting on every
+/// operation and for pagination on collection GETs.
+pub fn check_rate_limiting(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let operations = operations(spec);
+    let mut documents_headers = false;
+
+    for op in &operations {
+        let headers = response_headers(spec, op);
+        let has_rate_limit_headers = headers
+            .iter()
+            .any(|header| RATE_LIMIT_HEADERS.contains(&header.as_str()));
+        documents_headers |= has_rate_limit_headers;
+
+        if !op.documents_response("429") && !has_rate_limit_headers {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::NO_RATE_LIMIT_DOCUMENTED_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Operation {} documents neither a 429 response nor rate limit headers. Nothing tells clients (or reviewers) that its consumption is restricted", op.label()), 0, WeightScore::Medium));
+        } else if op.documents_response("429") && !too_many_requests_has_retry_hint(spec, op) {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::TOO_MANY_REQUESTS_WITHOUT_RETRY_AFTER_VALUE,
+            );
+            fixables.push(Fixable::new(format!("The 429 response of {} doesn't document a `Retry-After` or `RateLimit-Reset` header, so clients can't back off properly", op.label()), 0, WeightScore::Low));
+        }
+
+        if op.method == "get" {
+            if let Some(max_items) = collection_max_items(spec, op) {
+                check_pagination(
+                    spec,
+                    op,
+                    max_items,
+                    final_security_category_score,
+                    &mut fixables,
+                );
+            }
+        }
+    }
+
+    if !operations.is_empty() && !documents_headers {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::NO_RATE_LIMIT_HEADERS_VALUE,
+        );
+        fixables.push(Fixable::new("No operation documents rate limit response headers (`RateLimit-*`, `X-RateLimit-*` or `Retry-After`). Document the limits so clients can stay within them", 0, WeightScore::Low));
+    }
+
+    fixables
+}
+
+fn check_pagination(
+    spec: &Value,
+    op: &OperationRef,
+    max_items: Option<u64>,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let query_parameters: Vec<&Value> = op
+        .parameters(spec)
+        .into_iter()
+        .filter(|p| p.get("in").and_then(Value::as_str) == Some("query"))
+        .collect();
+    let name_of = |p: &Value| {
+        p.get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+    let paginated = query_parameters
+        .iter()
+        .any(|p| PAGINATION_PARAMETERS.contains(&name_of(p).as_str()));
+
+    if !paginated {
+        match max_items {
+            Some(max_items) => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::COLLECTION_WITHOUT_PAGINATION_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Collection endpoint {} has no pagination parameters. Responses are capped at {} items but clients can't page through the rest", op.label(), max_items), 0, WeightScore::Low));
+            }
+            None => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::UNBOUNDED_COLLECTION_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Collection endpoint {} has no pagination parameters and no `maxItems` on the returned array. A single request can return the whole collection", op.label()), 0, WeightScore::Medium));
+            }
+        }
+        return;
+    }
+
+    for parameter in query_parameters {
+        let name = name_of(parameter);
+        if !PAGE_SIZE_PARAMETERS.contains(&name.as_str()) {
+            continue;
+        }
+        let bounded = parameter
+            .get("schema")
+            .map(|schema| resolve_ref(spec, schema).get("maximum").is_some())
+            .unwrap_or(false);
+        if !bounded {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNBOUNDED_PAGE_SIZE_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Page size parameter `{}` of {} has no `maximum`. Clients can ask for arbitrarily large pages", name, op.label()), 0, WeightScore::Medium));
+        }
+    }
+}
+
+/// Lower cased names of the headers documented on every response of the operation
+fn response_headers(spec: &Value, op: &OperationRef) -> Vec<String> {
+    op.operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .map(|responses| {
+            responses
+                .values()
+                .filter_map(|response| resolve_ref(spec, response).get("headers"))
+                .filter_map(Value::as_object)
+                .flat_map(|headers| headers.keys().map(|h| h.to_lowercase()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn too_many_requests_has_retry_hint(spec: &Value, op: &OperationRef) -> bool {
+    let Some(response) = op.operation.pointer("/responses/429") else {
+        // documented through a `4XX` range, there's nothing specific to inspect
+        return true;
+    };
+    resolve_ref(spec, response)
+        .get("headers")
+        .and_then(Value::as_object)
+        .map(|headers| {
+            headers.keys().any(|header| {
+                let header = header.to_lowercase();
+                header == "retry-after" || header.ends_with("ratelimit-reset")
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// When the operation returns a collection, the `maxItems` of the returned array (if any).
+/// <br>A collection is a 2xx response whose schema is an array or wraps one, or a path ending in `/all`, `/list` etc.
+fn collection_max_items(spec: &Value, op: &OperationRef) -> Option<Option<u64>> {
+    if op.path.trim_end_matches('/').ends_with('}') {
+        return None;
+    }
+    let mut arrays: Vec<&Value> = Vec::new();
+    if let Some(responses) = op.operation.get("responses").and_then(Value::as_object) {
+        for (code, response) in responses {
+            if !code.starts_with('2') {
+                continue;
+            }
+            for schema in content_schemas(spec, response) {
+                if schema.get("type").and_then(Value::as_str) == Some("array") {
+                    arrays.push(schema);
+                } else if let Some(properties) = schema.get("properties").and_then(Value::as_object)
+                {
+                    arrays.extend(
+                        properties
+                            .values()
+                            .map(|property| resolve_ref(spec, property))
+                            .filter(|property| {
+                                property.get("type").and_then(Value::as_str) == Some("array")
+                            }),
+                    );
+                }
+            }
+        }
+    }
+    let last_segment = op
+        .path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    if arrays.is_empty() && !COLLECTION_SEGMENTS.contains(&last_segment.to_lowercase().as_str()) {
+        return None;
+    }
+    // an array without a bound makes the whole collection unbounded
+    let max_items = arrays
+        .iter()
+        .map(|array| array.get("maxItems").and_then(Value::as_u64))
+        .try_fold(0u64, |max, items| items.map(|items| max.max(items)));
+    Some(max_items.filter(|_| !arrays.is_empty()))
+}
@@ -0,0 +1,67 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::EffectiveSecurity;
+use crate::rules::operations;
+
+/// Check every operation documents the error responses clients will run into and list the
+/// missing status codes per endpoint.
+pub fn check_response_codes(
+    spec: &Value,
+    final_data_validation_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut coverage =
+        ReportTable::new("Missing response codes", &["Endpoint", "Method", "Missing"]);
+
+    for op in operations(spec) {
+        let mut missing: Vec<&str> = Vec::new();
+
+        if !EffectiveSecurity::of(spec, op.operation).is_unauthenticated() {
+            for code in ["401", "403"] {
+                if !op.documents_response(code) {
+                    missing.push(code);
+                }
+            }
+        }
+        let parameters = op.parameters(spec);
+        let takes_input = op.operation.get("requestBody").is_some() || !parameters.is_empty();
+        if takes_input && !op.documents_response("400") && !op.documents_response("422") {
+            missing.push("400/422");
+        }
+        // a missing 429 is reported by the rate limiting check
+        if op.operation.pointer("/responses/default").is_none() && !op.documents_response_class('5')
+        {
+            missing.push("default/5XX");
+        }
+        let has_path_parameters = op.path.contains('{')
+            || parameters
+                .iter()
+                .any(|p| p.get("in").and_then(Value::as_str) == Some("path"));
+        if has_path_parameters && !op.documents_response("404") {
+            missing.push("404");
+        }
+
+        if !missing.is_empty() {
+            for _ in &missing {
+                modify_score(
+                    final_data_validation_category_score,
+                    IssueScoreImpact::MISSING_ERROR_RESPONSE_VALUE,
+                );
+            }
+            fixables.push(Fixable::new(format!("Operation {} doesn't document the {} response(s). Undocumented error responses leave clients guessing and hide how the API fails", op.label(), missing.join(", ")), 0, WeightScore::Medium));
+            coverage.add_row(vec![
+                op.path.to_string(),
+                op.method.to_uppercase(),
+                missing.join(", "),
+            ]);
+        }
+    }
+
+    if !coverage.rows.is_empty() {
+        tables.push(coverage);
+    }
+    fixables
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, SecurityScheme, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::auth::{parse_requirements, SecurityRequirement};
+use crate::rules::{operations, resolve_ref};
+
+/// Deserialize every scheme under `components/securitySchemes`, keyed by scheme name.
+/// <br>Schemes that can't be read are reported as fixables and kept as `None`: they're defined,
+/// requirements referencing them aren't dangling.
+pub fn security_schemes(
+    spec: &Value,
+    fixables: &mut Vec<Fixable>,
+) -> BTreeMap<String, Option<SecurityScheme>> {
+    let mut schemes = BTreeMap::new();
+    if let Some(defined) = spec
+        .pointer("/components/securitySchemes")
+        .and_then(Value::as_object)
+    {
+        for (name, scheme) in defined {
+            match serde_json::from_value::<SecurityScheme>(resolve_ref(spec, scheme).clone()) {
+                Ok(scheme) => {
+                    schemes.insert(name.clone(), Some(scheme));
+                }
+                Err(e) => {
+                    schemes.insert(name.clone(), None);
+                    fixables.push(Fixable::new(
+                        format!(
+                            "Security scheme `{}` is not a valid security scheme object: {}",
+                            name, e
+                        ),
+                        0,
+                        WeightScore::Medium,
+                    ))
+                }
+            }
+        }
+    }
+    schemes
+}
+
+/// Every security requirement declared in the document along with where it was declared,
+/// `root` for the root level `security` array or the operation label.
+pub fn declared_requirements(spec: &Value) -> Vec<(String, SecurityRequirement)> {
+    let mut declared = Vec::new();
+    if let Some(security) = spec.get("security") {
+        for requirement in parse_requirements(security) {
+            declared.push(("root".to_string(), requirement));
+        }
+    }
+    for op in operations(spec) {
+        if let Some(security) = op.operation.get("security") {
+            for requirement in parse_requirements(security) {
+                declared.push((op.label(), requirement));
+            }
+        }
+    }
+    declared
+}
+
+/// Inspect each scheme of `components/securitySchemes` for weak authentication and check that
+/// security requirements and schemes reference each other properly.
+pub fn check_security_schemes(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let schemes = security_schemes(spec, &mut fixables);
+    let has_csrf_protection = has_csrf_protection(spec, &schemes);
+
+    let valid = schemes
+        .iter()
+        .filter_map(|(name, scheme)| scheme.as_ref().map(|scheme| (name, scheme)));
+    for (name, scheme) in valid {
+        let http_scheme = scheme.scheme.as_deref().unwrap_or_default().to_lowercase();
+        let location = scheme.in_.as_deref().unwrap_or_default();
+        match scheme.type_.as_str() {
+            "http" if http_scheme == "basic" => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::HTTP_BASIC_AUTH_SCHEME_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Security scheme `{}` uses HTTP basic authentication. Credentials are only base64 encoded and sent with every request, use a token based scheme instead", name), 0, WeightScore::High));
+            }
+            "http" if http_scheme == "bearer" && scheme.bearer_format.is_none() => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::BEARER_WITHOUT_FORMAT_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Bearer security scheme `{}` has no `bearerFormat`. Document the token format (e.g. JWT) so clients and reviewers know what to expect", name), 0, WeightScore::Low));
+            }
+            "apiKey" if location == "query" => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::API_KEY_IN_QUERY_VALUE,
+                );
+                fixables.push(Fixable::new(format!("API key security scheme `{}` is sent in the query string. URLs end up in logs, browser history and proxies, send the key in a header instead", name), 0, WeightScore::High));
+            }
+            "apiKey" if location == "cookie" && !has_csrf_protection => {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::COOKIE_API_KEY_WITHOUT_CSRF_VALUE,
+                );
+                fixables.push(Fixable::new(format!("API key security scheme `{}` is sent in a cookie but no CSRF protection (e.g. an `X-CSRF-Token` header) is documented. Browsers attach cookies to cross-site requests automatically", name), 0, WeightScore::Medium));
+            }
+            _ => {}
+        }
+    }
+
+    let declared = declared_requirements(spec);
+    let mut reported: Vec<(&str, &str)> = Vec::new();
+    for (location, requirement) in &declared {
+        for (name, _) in requirement {
+            if !schemes.contains_key(name) && !reported.contains(&(location, name)) {
+                reported.push((location, name));
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::UNDEFINED_SECURITY_SCHEME_REFERENCE_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Security requirement of {} references scheme `{}` which is not defined in `components/securitySchemes`. The requirement can't be enforced", location, name), 0, WeightScore::Critical));
+            }
+        }
+    }
+
+    for name in schemes.keys() {
+        let referenced = declared
+            .iter()
+            .any(|(_, requirement)| requirement.iter().any(|(n, _)| n == name));
+        if !referenced {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::UNUSED_SECURITY_SCHEME_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Security scheme `{}` is defined but never referenced by a security requirement. Remove it or apply it to the operations it is meant to protect", name), 0, WeightScore::Low));
+        }
+    }
+
+    fixables
+}
+
+/// A CSRF token is documented either as a header parameter of some operation or as a header api key scheme
+fn has_csrf_protection(spec: &Value, schemes: &BTreeMap<String, Option<SecurityScheme>>) -> bool {
+    let is_csrf_name = |name: &str| {
+        let name = name.to_lowercase();
+        name.contains("csrf") || name.contains("xsrf")
+    };
+    let csrf_scheme = schemes.values().flatten().any(|scheme| {
+        scheme.in_.as_deref() == Some("header")
+            && is_csrf_name(scheme.name.as_deref().unwrap_or_default())
+    });
+    csrf_scheme
+        || operations(spec).iter().any(|op| {
+            op.parameters(spec).iter().any(|parameter| {
+                parameter.get("in").and_then(Value::as_str) == Some("header")
+                    && is_csrf_name(
+                        parameter
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default(),
+                    )
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings(spec: &Value) -> Vec<String> {
+        check_security_schemes(spec, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn weak_schemes_and_undefined_references_are_reported() {
+        let spec = json!({
+            "security": [{ "Basic": [] }, { "Missing": [] }],
+            "components": { "securitySchemes": {
+                "Basic": { "type": "http", "scheme": "basic" }
+            }}
+        });
+        let found = findings(&spec);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("uses HTTP basic authentication"));
+        assert!(found[1].contains("references scheme `Missing` which is not defined"));
+    }
+
+    #[test]
+    fn invalid_schemes_are_reported_but_still_defined() {
+        let spec = json!({
+            "security": [{ "Broken": [] }],
+            "components": { "securitySchemes": {
+                "Broken": { "scheme": "bearer" }
+            }}
+        });
+        let schemes = security_schemes(&spec, &mut Vec::new());
+        assert!(matches!(schemes.get("Broken"), Some(None)));
+
+        let found = findings(&spec);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("`Broken` is not a valid security scheme object"));
+    }
+}
@@ -0,0 +1,199 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{capitalise, name_has_word, operations};
+
+/// Kinds of sensitive data recognised from the words of a parameter name (see
+/// [`name_has_word`]), whether they're credentials and the words, most critical first
+const SENSITIVE_NAME_WORDS: [(&str, bool, &[&str]); 9] = [
+    (
+        "a password",
+        true,
+        &[
+            "password",
+            "passwd",
+            "pass",
+            "passcode",
+            "passphrase",
+            "pwd",
+            "pin",
+        ],
+    ),
+    ("a secret", true, &["secret", "privatekey", "credential"]),
+    // a bare `key` is usually an object key (`sortKey`, `partitionKey`)
+    ("an API key", true, &["apikey", "accesskey"]),
+    (
+        "a token",
+        true,
+        &[
+            "token",
+            "accesstoken",
+            "refreshtoken",
+            "idtoken",
+            "authtoken",
+            "bearertoken",
+            "sessiontoken",
+            "jwt",
+            "auth",
+            "authorization",
+        ],
+    ),
+    (
+        "a session ID",
+        true,
+        &[
+            "session",
+            "sessionid",
+            "sessid",
+            "sid",
+            "phpsessid",
+            "jsessionid",
+        ],
+    ),
+    (
+        "a card number",
+        false,
+        &[
+            "cardno",
+            "cardnum",
+            "cardnumber",
+            "pan",
+            "cvv",
+            "cvv2",
+            "cvc",
+            "iban",
+        ],
+    ),
+    (
+        "a national ID",
+        false,
+        &[
+            "ssn",
+            "socialsecurity",
+            "nationalid",
+            "nin",
+            "bvn",
+            "passport",
+            "taxid",
+            "tin",
+        ],
+    ),
+    ("an email address", false, &["email", "mail"]),
+    (
+        "a phone number",
+        false,
+        &[
+            "phone",
+            "telephone",
+            "mobile",
+            "mobilephone",
+            "msisdn",
+            "cellno",
+        ],
+    ),
+];
+/// Token names that are pagination cursors rather than credentials
+const CURSOR_TOKEN_NAMES: [&str; 4] = ["pagetoken", "page_token", "nexttoken", "next_token"];
+
+/// Secrets and personal data in query or path parameters end up in access logs, proxies and
+/// browser history.
+pub fn check_sensitive_data_in_url(
+    spec: &Value,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    for op in operations(spec) {
+        for parameter in op.parameters(spec) {
+            let location = parameter
+                .get("in")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if location != "query" && location != "path" {
+                continue;
+            }
+            let name = parameter
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if CURSOR_TOKEN_NAMES.contains(&name.to_lowercase().as_str()) {
+                continue;
+            }
+            let Some((kind, is_credential, _)) = SENSITIVE_NAME_WORDS
+                .iter()
+                .find(|(_, _, words)| name_has_word(name, words))
+            else {
+                continue;
+            };
+
+            let (weight, impact) = if *is_credential {
+                (WeightScore::High, IssueScoreImpact::CREDENTIAL_IN_URL_VALUE)
+            } else {
+                (
+                    WeightScore::Medium,
+                    IssueScoreImpact::PERSONAL_DATA_IN_URL_VALUE,
+                )
+            };
+            let suggestion = if op.method == "get" && location == "query" {
+                "GET requests carry their query string in the URL, send it in a header or switch to a POST request body"
+            } else {
+                "send it in a header or in the request body instead"
+            };
+            modify_score(final_security_category_score, impact);
+            fixables.push(Fixable::new(format!("{} parameter `{}` of {} carries {}. URLs are written to server and proxy logs and browser history, {}", capitalise(location), name, op.label(), kind, suggestion), 0, weight));
+        }
+    }
+
+    fixables
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings_for_query_parameter(name: &str) -> Vec<Fixable> {
+        let spec = json!({
+            "paths": { "/items": { "get": {
+                "parameters": [{ "name": name, "in": "query", "schema": { "type": "string" } }],
+                "responses": {},
+            } } },
+        });
+        check_sensitive_data_in_url(&spec, &mut 30)
+    }
+
+    #[test]
+    fn sensitive_names_are_flagged() {
+        for name in [
+            "password",
+            "userPassword",
+            "api_key",
+            "X-API-Key",
+            "accessKey",
+            "access_token",
+            "JSESSIONID",
+            "cardNumber",
+            "email",
+        ] {
+            assert_eq!(findings_for_query_parameter(name).len(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn words_merely_containing_sensitive_keywords_are_not_flagged() {
+        for name in [
+            "bypass",
+            "compass",
+            "keyword",
+            "sortKey",
+            "itemKey",
+            "partitionKey",
+            "spinner",
+            "pageToken",
+            "pandemic",
+        ] {
+            assert!(findings_for_query_parameter(name).is_empty(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::net::IpAddr;
+
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{operations, resolve_ref};
+
+/// Hostname suffixes that only resolve inside a private network
+const INTERNAL_HOST_SUFFIXES: [&str; 5] = [".local", ".internal", ".intranet", ".corp", ".lan"];
+
+/// Examine every server URL (root, path and operation level): plain HTTP, internal hosts,
+/// unrestricted server variables and a mix of HTTP and HTTPS servers.
+pub fn check_servers(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let servers = declared_servers(spec);
+
+    let mut has_http = false;
+    let mut has_https = false;
+    for (location, server) in &servers {
+        let Some(template) = server.get("url").and_then(Value::as_str) else {
+            continue;
+        };
+        let url = expand_variables(template, server);
+        // relative server URLs inherit the scheme the specification is served with
+        let scheme = match url.split_once("://") {
+            Some((scheme, _)) => scheme.to_lowercase(),
+            None => String::new(),
+        };
+        has_http |= scheme == "http";
+        has_https |= scheme == "https";
+
+        if scheme == "http" {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::PLAIN_HTTP_SERVER_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Server `{}` ({}) uses plain HTTP. Credentials and data travel unencrypted, serve the API over HTTPS only", template, location), 0, WeightScore::High));
+        }
+
+        let host = host_of(&url);
+        if is_internal_host(&host) {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::INTERNAL_SERVER_ADDRESS_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Server `{}` ({}) points at a localhost, loopback or private network address `{}`. Development and internal hosts shouldn't ship in a published specification", template, location, host), 0, WeightScore::Medium));
+        }
+
+        if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+            for (name, variable) in variables {
+                let restricted = variable
+                    .get("enum")
+                    .and_then(Value::as_array)
+                    .map(|values| !values.is_empty())
+                    .unwrap_or(false);
+                if !restricted {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::UNRESTRICTED_SERVER_VARIABLE_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("Server variable `{}` of `{}` ({}) has no `enum`, so clients can be pointed at any host. Restrict it to the allowed values", name, template, location), 0, WeightScore::Medium));
+                }
+            }
+        }
+    }
+
+    if has_http && has_https {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MIXED_HTTP_HTTPS_SERVERS_VALUE,
+        );
+        fixables.push(Fixable::new("The API is served over both HTTP and HTTPS. Clients may downgrade to the plain HTTP server, remove it or redirect it to HTTPS", 0, WeightScore::High));
+    }
+
+    fixables
+}
+
+/// Servers declared at the root, path item and operation level, along with where they're declared
+fn declared_servers(spec: &Value) -> Vec<(String, &Value)> {
+    fn push<'a>(
+        servers: &mut Vec<(String, &'a Value)>,
+        location: String,
+        declared: Option<&'a Value>,
+    ) {
+        for server in declared.and_then(Value::as_array).into_iter().flatten() {
+            servers.push((location.clone(), server));
+        }
+    }
+    let mut servers: Vec<(String, &Value)> = Vec::new();
+    push(&mut servers, "root".to_string(), spec.get("servers"));
+    // path items without operations still declare servers
+    if let Some(paths) = spec.get("paths").and_then(Value::as_object) {
+        for (path, path_item) in paths {
+            push(
+                &mut servers,
+                format!("path {}", path),
+                resolve_ref(spec, path_item).get("servers"),
+            );
+        }
+    }
+    for op in operations(spec) {
+        push(&mut servers, op.label(), op.operation.get("servers"));
+    }
+    servers
+}
+
+/// Substitute `{variable}`s in a server URL with their default values
+fn expand_variables(url: &str, server: &Value) -> String {
+    let mut expanded = url.to_string();
+    if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+        for (name, variable) in variables {
+            if let Some(default) = variable.get("default").and_then(Value::as_str) {
+                expanded = expanded.replace(&format!("{{{}}}", name), default);
+            }
+        }
+    }
+    expanded
+}
+
+/// Host part of an absolute URL, without port or IPv6 brackets
+pub fn host_of(url: &str) -> String {
+    let Some((_, rest)) = url.split_once("://") else {
+        return String::new();
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+    let host = if let Some(ipv6) = authority.strip_prefix('[') {
+        ipv6.split(']').next().unwrap_or_default()
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    host.to_lowercase()
+}
+
+/// localhost, loopback, private network and link local hosts
+pub fn is_internal_host(host: &str) -> bool {
+    if host.is_empty() {
+        return false;
+    }
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    if INTERNAL_HOST_SUFFIXES
+        .iter()
+        .any(|suffix| host.ends_with(suffix))
+    {
+        return true;
+    }
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Ok(IpAddr::V6(ip)) => {
+            // unique local (fc00::/7) and link local (fe80::/10) addresses
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings(spec: &Value) -> Vec<String> {
+        check_servers(spec, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn plain_http_and_mixed_schemes_are_reported() {
+        let spec = json!({ "servers": [
+            { "url": "https://api.example.com" },
+            { "url": "http://api.example.com" }
+        ]});
+        let found = findings(&spec);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("`http://api.example.com` (root) uses plain HTTP"));
+        assert!(found[1].contains("both HTTP and HTTPS"));
+
+        let spec = json!({ "servers": [{ "url": "https://api.example.com" }, { "url": "/v1" }] });
+        assert!(findings(&spec).is_empty());
+    }
+
+    #[test]
+    fn internal_hosts_are_reported() {
+        for host in ["localhost", "10.0.0.12", "[fd00::1]", "billing.internal"] {
+            let spec = json!({ "servers": [{ "url": format!("https://{}:8443/v1", host) }] });
+            let found = findings(&spec);
+            assert_eq!(found.len(), 1, "{}", host);
+            assert!(found[0].contains("private network address"));
+        }
+        assert!(!is_internal_host("internal.example.com"));
+        assert!(!is_internal_host("8.8.8.8"));
+    }
+
+    #[test]
+    fn server_variables_need_an_enum() {
+        let spec = json!({ "servers": [{
+            "url": "https://{tenant}.example.com/{version}",
+            "variables": {
+                "tenant": { "default": "acme" },
+                "version": { "default": "v1", "enum": ["v1", "v2"] }
+            }
+        }]});
+        let found = findings(&spec);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("Server variable `tenant`"));
+    }
+
+    #[test]
+    fn servers_of_path_items_without_operations_are_checked() {
+        let spec = json!({ "paths": { "/legacy": {
+            "servers": [{ "url": "http://legacy.example.com" }]
+        }}});
+        let found = findings(&spec);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("(path /legacy) uses plain HTTP"));
+    }
+}
@@ -0,0 +1,203 @@
+use serde_json::Value;
+
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::{
+    content_schemas, name_has_word, operations, resolve_ref, visit_properties, OperationRef,
+};
+
+/// Words of parameter and property names suggesting a URL the server will fetch or redirect to
+const URL_NAME_WORDS: [&str; 12] = [
+    "url",
+    "uri",
+    "href",
+    "callback",
+    "webhook",
+    "redirect",
+    "returnto",
+    "endpoint",
+    "proxy",
+    "feed",
+    "fetch",
+    "targethost",
+];
+/// String formats holding a URL
+const URL_FORMATS: [&str; 5] = ["uri", "url", "uri-reference", "iri", "iri-reference"];
+
+/// OWASP API7 (server side request forgery): URL-like inputs without a `pattern` or `enum`, and
+/// callbacks or links whose targets come from request data.
+pub fn check_ssrf(spec: &Value, final_security_category_score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+
+    for op in operations(spec) {
+        for parameter in op.parameters(spec) {
+            let name = parameter
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let location = parameter
+                .get("in")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let schema = parameter
+                .get("schema")
+                .map(|schema| resolve_ref(spec, schema))
+                .unwrap_or(&Value::Null);
+            if is_unrestricted_url(name, schema) {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::UNRESTRICTED_URL_INPUT_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Parameter `{}` ({}) of {} looks like a URL the server will fetch or redirect to but has no `pattern` or `enum`. Restrict it to allowed hosts to prevent SSRF", name, location, op.label()), 0, WeightScore::High));
+            }
+        }
+
+        if let Some(request_body) = op.operation.get("requestBody") {
+            let mut reported: Vec<String> = Vec::new();
+            for schema in content_schemas(spec, request_body) {
+                visit_properties(spec, schema, &mut |path, property| {
+                    let name = path.rsplit('.').next().unwrap_or(path);
+                    if is_unrestricted_url(name, property) && !reported.iter().any(|p| p == path) {
+                        reported.push(path.to_string());
+                    }
+                });
+            }
+            for path in reported {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::UNRESTRICTED_URL_INPUT_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Request body property `{}` of {} looks like a URL the server will fetch or redirect to but has no `pattern` or `enum`. Restrict it to allowed hosts to prevent SSRF", path, op.label()), 0, WeightScore::High));
+            }
+        }
+
+        check_callbacks(spec, &op, final_security_category_score, &mut fixables);
+        check_links(spec, &op, final_security_category_score, &mut fixables);
+    }
+
+    fixables
+}
+
+fn is_unrestricted_url(name: &str, schema: &Value) -> bool {
+    let is_string = match schema.get("type") {
+        Some(type_) => type_.as_str() == Some("string"),
+        None => true,
+    };
+    let format = schema
+        .get("format")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let url_like =
+        URL_FORMATS.contains(&format) || (is_string && name_has_word(name, &URL_NAME_WORDS));
+    url_like && schema.get("pattern").is_none() && schema.get("enum").is_none()
+}
+
+/// Callback URLs are runtime expressions, `{$request.body#/callbackUrl}` makes the server call
+/// whatever the client asks it to.
+fn check_callbacks(
+    spec: &Value,
+    op: &OperationRef,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let Some(callbacks) = op.operation.get("callbacks").and_then(Value::as_object) else {
+        return;
+    };
+    for (name, callback) in callbacks {
+        let Some(expressions) = resolve_ref(spec, callback).as_object() else {
+            continue;
+        };
+        for expression in expressions.keys().filter(|e| e.contains("$request.")) {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::REQUEST_DRIVEN_CALLBACK_VALUE,
+            );
+            fixables.push(Fixable::new(format!("Callback `{}` of {} is sent to `{}`, a URL taken from the request. Validate callback URLs against an allow list before the server calls them", name, op.label(), expression), 0, WeightScore::High));
+        }
+    }
+}
+
+/// Links whose server or parameters are built from request data
+fn check_links(
+    spec: &Value,
+    op: &OperationRef,
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let Some(responses) = op.operation.get("responses").and_then(Value::as_object) else {
+        return;
+    };
+    for (code, response) in responses {
+        let Some(links) = resolve_ref(spec, response)
+            .get("links")
+            .and_then(Value::as_object)
+        else {
+            continue;
+        };
+        for (name, link) in links {
+            let link = resolve_ref(spec, link);
+            let from_request = [
+                "/operationRef",
+                "/server/url",
+                "/parameters",
+                "/requestBody",
+            ]
+            .iter()
+            .filter_map(|pointer| link.pointer(pointer))
+            .any(|value| value.to_string().contains("$request."));
+            if from_request {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::REQUEST_DRIVEN_LINK_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Link `{}` of the {} response of {} builds its target from request data (`$request.` expression). Make sure clients can't steer follow-up requests to arbitrary hosts", name, code, op.label()), 0, WeightScore::Medium));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn findings_for_body_property(name: &str) -> Vec<Fixable> {
+        let spec = json!({
+            "paths": { "/items": { "post": {
+                "requestBody": { "content": { "application/json": { "schema": {
+                    "type": "object",
+                    "properties": { name: { "type": "string" } },
+                } } } },
+                "responses": {},
+            } } },
+        });
+        check_ssrf(&spec, &mut 30)
+    }
+
+    #[test]
+    fn url_like_names_are_flagged() {
+        for name in [
+            "callbackUrl",
+            "redirect_uri",
+            "webHook",
+            "returnTo",
+            "feedUrls",
+        ] {
+            assert_eq!(findings_for_body_property(name).len(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn words_merely_containing_url_keywords_are_not_flagged() {
+        for name in [
+            "securityCode",
+            "feedback",
+            "prefetchCount",
+            "curiosity",
+            "purl",
+        ] {
+            assert!(findings_for_body_property(name).is_empty(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::servers::{host_of, is_internal_host};
+use crate::wsdl::policy::{self, PolicyAssertions};
+use crate::wsdl::{Binding, Port, Service, WsdlDocument};
+
+/// Ports web services are expected on
+const STANDARD_PORTS: [u16; 2] = [80, 443];
+
+/// Check the `soap:address`/`soap12:address` of every service port: plain HTTP, internal hosts,
+/// non standard ports and SOAP 1.1/1.2 ports of the same port type secured differently, by
+/// policy or by address scheme.
+pub fn check_soap_endpoints(
+    wsdl: &WsdlDocument,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+
+    for service in &wsdl.services {
+        for port in &service.ports {
+            let Some(location) = &port.address else {
+                continue;
+            };
+            let label = format!(
+                "Port `{}` of service `{}` (line {})",
+                port.name, service.name, port.line
+            );
+            if scheme_of(location) == "http" {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::PLAIN_HTTP_SERVER_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} is served over plain HTTP ({}). SOAP envelopes, credentials in WS-Security headers included, travel unencrypted, use HTTPS", label, location), port.line, WeightScore::High));
+            }
+            let host = host_of(location);
+            if is_internal_host(&host) {
+                modify_score(
+                    final_security_category_score,
+                    IssueScoreImpact::INTERNAL_SERVER_ADDRESS_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} points at a localhost, loopback or private network address `{}`. Internal hosts shouldn't ship in a published WSDL", label, host), port.line, WeightScore::Medium));
+            }
+            if let Some(number) = port_of(location) {
+                if !STANDARD_PORTS.contains(&number) {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::NON_STANDARD_PORT_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("{} listens on non standard port {} ({}). Such ports often expose application servers or admin consoles directly instead of going through a gateway", label, number, location), port.line, WeightScore::Low));
+                }
+            }
+        }
+
+        // the same port type exposed over SOAP 1.1 and SOAP 1.2
+        let ports: Vec<(&Port, &Binding)> = service
+            .ports
+            .iter()
+            .filter_map(|port| {
+                port.binding
+                    .as_ref()
+                    .and_then(|b| wsdl.binding(b))
+                    .map(|b| (port, b))
+            })
+            .collect();
+        for (i, (port, binding)) in ports.iter().enumerate() {
+            for (other_port, other_binding) in &ports[i + 1..] {
+                let same_port_type =
+                    binding.port_type.is_some() && binding.port_type == other_binding.port_type;
+                if !same_port_type || binding.soap_version == other_binding.soap_version {
+                    continue;
+                }
+                let scheme = port.address.as_deref().map(scheme_of).unwrap_or_default();
+                let other_scheme = other_port
+                    .address
+                    .as_deref()
+                    .map(scheme_of)
+                    .unwrap_or_default();
+                let security = binding_security(wsdl, service, port, binding);
+                let other_security = binding_security(wsdl, service, other_port, other_binding);
+                if scheme != other_scheme || security != other_security {
+                    modify_score(
+                        final_security_category_score,
+                        IssueScoreImpact::INCONSISTENT_PORT_SECURITY_VALUE,
+                    );
+                    fixables.push(Fixable::new(format!("Service `{}` exposes port type `{}` through port `{}` ({} over {}, {}) and port `{}` ({} over {}, {}) with different security. Callers can pick the weaker one, secure both alike", service.name, binding.port_type.as_ref().map(|p| p.name.as_str()).unwrap_or_default(), port.name, version_of(binding), scheme_or_unknown(&scheme), describe(&security), other_port.name, version_of(other_binding), scheme_or_unknown(&other_scheme), describe(&other_security)), port.line, WeightScore::High));
+                }
+            }
+        }
+    }
+
+    fixables
+}
+
+/// Lower case scheme of an absolute URL, empty for relative ones
+fn scheme_of(url: &str) -> String {
+    url.split_once("://")
+        .map(|(scheme, _)| scheme.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn scheme_or_unknown(scheme: &str) -> &str {
+    if scheme.is_empty() {
+        "unknown scheme"
+    } else {
+        scheme
+    }
+}
+
+/// Policy alternatives of each operation of a binding at a port, by operation name
+fn binding_security(
+    wsdl: &WsdlDocument,
+    service: &Service,
+    port: &Port,
+    binding: &Binding,
+) -> Vec<(String, Vec<PolicyAssertions>)> {
+    let mut security: Vec<(String, Vec<PolicyAssertions>)> = binding
+        .operations
+        .iter()
+        .map(|operation| {
+            let alternatives = wsdl.operation_policy(binding, operation, Some((service, port)));
+            (operation.name.clone(), alternatives)
+        })
+        .collect();
+    security.sort_by(|a, b| a.0.cmp(&b.0));
+    security
+}
+
+fn describe(security: &[(String, Vec<PolicyAssertions>)]) -> String {
+    let mut descriptions: Vec<String> = security
+        .iter()
+        .map(|(_, alternatives)| policy::describe(alternatives))
+        .collect();
+    descriptions.dedup();
+    if descriptions.is_empty() {
+        "no operations".to_string()
+    } else {
+        descriptions.join(" / ")
+    }
+}
+
+fn version_of(binding: &Binding) -> String {
+    binding
+        .soap_version
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "non SOAP".to_string())
+}
+
+/// Explicit port of an absolute URL
+fn port_of(url: &str) -> Option<u16> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit('@').next().unwrap_or_default();
+    let after_host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6
+            .split_once(']')
+            .map(|(_, rest)| rest)
+            .unwrap_or_default(),
+        None => authority,
+    };
+    after_host
+        .rsplit_once(':')
+        .and_then(|(_, port)| port.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inconsistent_findings(soap_11_address: &str, soap_12_address: &str) -> Vec<String> {
+        let wsdl = WsdlDocument::parse(&format!(
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/" xmlns:tns="urn:orders" targetNamespace="urn:orders">
+  <portType name="Orders"><operation name="Place"/></portType>
+  <binding name="Soap11" type="tns:Orders">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place"/>
+  </binding>
+  <binding name="Soap12" type="tns:Orders">
+    <soap12:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place"/>
+  </binding>
+  <service name="OrderService">
+    <port name="Soap11Port" binding="tns:Soap11"><soap:address location="{}"/></port>
+    <port name="Soap12Port" binding="tns:Soap12"><soap12:address location="{}"/></port>
+  </service>
+</definitions>"#,
+            soap_11_address, soap_12_address
+        ))
+        .unwrap();
+        check_soap_endpoints(&wsdl, &mut 30)
+            .iter()
+            .map(Fixable::to_string)
+            .filter(|message| message.contains("different security"))
+            .collect()
+    }
+
+    #[test]
+    fn ports_differing_only_by_scheme_are_inconsistent() {
+        let findings = inconsistent_findings(
+            "https://orders.example.com/soap11",
+            "http://orders.example.com/soap12",
+        );
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].contains("over http,"));
+    }
+
+    #[test]
+    fn ports_with_the_same_scheme_and_policies_are_consistent() {
+        let findings = inconsistent_findings(
+            "https://orders.example.com/soap11",
+            "https://orders.example.com/soap12",
+        );
+        assert!(findings.is_empty());
+    }
+}
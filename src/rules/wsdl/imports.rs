@@ -0,0 +1,61 @@
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::capitalise;
+use crate::wsdl::imports::{Import, ImportStatus};
+use crate::wsdl::WsdlDocument;
+
+/// Imports and includes that couldn't be followed: the types they declare escape every schema
+/// check. Also lists the imports met in a table.
+pub fn check_imports(
+    wsdl: &WsdlDocument,
+    final_data_validation_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    if wsdl.imports.is_empty() {
+        return fixables;
+    }
+    let mut table = ReportTable::new("WSDL imports", &["Kind", "Location", "From", "Status"]);
+
+    for import in &wsdl.imports {
+        let status = match &import.status {
+            ImportStatus::Resolved(path) => format!("loaded from {}", path.display()),
+            ImportStatus::Unresolved(reason) => {
+                modify_score(
+                    final_data_validation_category_score,
+                    IssueScoreImpact::UNRESOLVED_IMPORT_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} of `{}` in {} (line {}) could not be resolved: {}. The types it declares are not analysed, make the file available next to the importing one or in the catalogue directory", capitalise_kind(import), import.location, origin(import), import.line, reason), import.line, WeightScore::Medium));
+                "unresolved".to_string()
+            }
+            ImportStatus::Cycle(path) => {
+                modify_score(
+                    final_data_validation_category_score,
+                    IssueScoreImpact::IMPORT_CYCLE_VALUE,
+                );
+                fixables.push(Fixable::new(format!("{} of `{}` in {} (line {}) imports {} again, which is already being imported. Cyclic imports confuse tooling, restructure them into a hierarchy", capitalise_kind(import), import.location, origin(import), import.line, path.display()), import.line, WeightScore::Low));
+                "cycle".to_string()
+            }
+        };
+        table.add_row(vec![
+            import.kind.to_string(),
+            import.location.clone(),
+            origin(import),
+            status,
+        ]);
+    }
+
+    tables.push(table);
+    fixables
+}
+
+fn origin(import: &Import) -> String {
+    match &import.origin {
+        Some(path) => path.display().to_string(),
+        None => "the scanned document".to_string(),
+    }
+}
+
+fn capitalise_kind(import: &Import) -> String {
+    capitalise(&import.kind.to_string())
+}
@@ -0,0 +1,18 @@
+//! Rules run against a WSDL (SOAP) service description, on the model of [`crate::wsdl`].
+
+pub mod endpoints;
+pub mod imports;
+pub mod operations;
+pub mod policy;
+pub mod schema;
+pub mod xml;
+
+use crate::wsdl::schema::Schema;
+
+/// ` in <file>` for schemas imported from another file, empty for embedded ones
+fn schema_origin(schema: &Schema) -> String {
+    match &schema.location {
+        Some(location) => format!(" in {}", location),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,164 @@
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::name_tokens;
+use crate::wsdl::policy::is_secured;
+use crate::wsdl::xml::QName;
+use crate::wsdl::{Binding, BindingOperation, WsdlDocument};
+
+/// First words (lower case) of operation names implying privileged or destructive actions,
+/// with what they imply
+const DANGEROUS_VERBS: [(&str, &str); 8] = [
+    ("delete", "deletes data"),
+    ("remove", "deletes data"),
+    ("purge", "deletes data"),
+    ("reverse", "reverses transactions"),
+    ("post", "posts (commits) entries"),
+    ("admin", "performs administrative actions"),
+    ("execute", "executes arbitrary actions"),
+    ("exec", "executes arbitrary actions"),
+];
+
+/// Build the SOAP operation inventory and flag operations whose names imply privileged or
+/// destructive actions so reviewers can prioritise them.
+pub fn check_soap_operations(
+    wsdl: &WsdlDocument,
+    final_security_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut inventory = ReportTable::new(
+        "SOAP operation inventory",
+        &[
+            "Port type",
+            "Operation",
+            "Input",
+            "Output",
+            "SOAPAction",
+            "Style",
+        ],
+    );
+
+    for port_type in &wsdl.port_types {
+        for operation in &port_type.operations {
+            let bound = bound_operation(wsdl, &port_type.name, &operation.name);
+            let message_name = |message: &Option<QName>| {
+                message
+                    .as_ref()
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            inventory.add_row(vec![
+                port_type.name.clone(),
+                operation.name.clone(),
+                message_name(&operation.input),
+                message_name(&operation.output),
+                bound
+                    .and_then(|(_, o)| o.soap_action.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+                bound
+                    .map(|(b, o)| {
+                        o.style
+                            .clone()
+                            .or_else(|| b.style.clone())
+                            .unwrap_or_else(|| "document".to_string())
+                    })
+                    .unwrap_or_else(|| "unbound".to_string()),
+            ]);
+
+            let Some(implied) = dangerous_action(&operation.name) else {
+                continue;
+            };
+            let secured = bound
+                .map(|(binding, bound)| is_secured(&wsdl.operation_policy(binding, bound, None)))
+                .unwrap_or(false);
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::DANGEROUS_SOAP_OPERATION_VALUE,
+            );
+            let (weight, advice) = if secured {
+                (
+                    WeightScore::Medium,
+                    "Prioritise it in reviews and make sure only privileged callers are authorised",
+                )
+            } else {
+                (
+                    WeightScore::High,
+                    "No security policy protects it, attach one requiring authentication and restrict it to privileged callers",
+                )
+            };
+            fixables.push(Fixable::new(
+                format!(
+                    "SOAP operation `{}` of port type `{}` (line {}) is named like an operation that {}. {}",
+                    operation.name, port_type.name, operation.line, implied, advice
+                ),
+                operation.line,
+                weight,
+            ));
+        }
+    }
+
+    tables.push(inventory);
+    fixables
+}
+
+/// The binding operation implementing `operation` of `port_type`, SOAP bindings first
+fn bound_operation<'a>(
+    wsdl: &'a WsdlDocument,
+    port_type: &str,
+    operation: &str,
+) -> Option<(&'a Binding, &'a BindingOperation)> {
+    let mut bindings: Vec<&Binding> = wsdl
+        .bindings
+        .iter()
+        .filter(|b| b.port_type.as_ref().map(|p| p.name.as_str()) == Some(port_type))
+        .collect();
+    bindings.sort_by_key(|b| b.soap_version.is_none());
+    bindings.into_iter().find_map(|binding| {
+        binding
+            .operations
+            .iter()
+            .find(|o| o.name == operation)
+            .map(|o| (binding, o))
+    })
+}
+
+/// What the first word of an operation name implies, `DeleteAccount` but not `Postpone`
+fn dangerous_action(operation: &str) -> Option<&'static str> {
+    let verb = name_tokens(operation).into_iter().next()?;
+    DANGEROUS_VERBS
+        .iter()
+        .find(|(dangerous, _)| *dangerous == verb)
+        .map(|(_, implied)| *implied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_starting_with_a_dangerous_verb_are_flagged() {
+        assert_eq!(dangerous_action("DeleteAccount"), Some("deletes data"));
+        assert_eq!(dangerous_action("purge_cache"), Some("deletes data"));
+        assert_eq!(
+            dangerous_action("PostEntries"),
+            Some("posts (commits) entries")
+        );
+        assert_eq!(
+            dangerous_action("execCommand"),
+            Some("executes arbitrary actions")
+        );
+    }
+
+    #[test]
+    fn words_merely_starting_like_a_dangerous_verb_are_not_flagged() {
+        for name in [
+            "ExecutionLog",
+            "Postpone",
+            "PostingGroups",
+            "PostedTransfers",
+            "GetAccount",
+        ] {
+            assert_eq!(dangerous_action(name), None, "{}", name);
+        }
+    }
+}
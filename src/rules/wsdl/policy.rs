@@ -0,0 +1,211 @@
+use crate::openapi::{Fixable, IssueScoreImpact, ReportTable, WeightScore};
+use crate::parser::modify_score;
+use crate::wsdl::policy::{describe, is_secured, PolicyAssertions};
+use crate::wsdl::{Binding, BindingOperation, WsdlDocument};
+
+/// Policy alternatives in effect for each operation of a binding
+type OperationPolicies<'w> = Vec<(&'w BindingOperation, Vec<PolicyAssertions>)>;
+
+/// Resolve the WS-Policy in effect for every binding operation and check its WS-SecurityPolicy
+/// assertions: missing policies, plain text UsernameToken passwords, timestamps and signatures.
+/// <br>Callers may pick any alternative of a policy, the weakest one is reported.
+pub fn check_ws_security(
+    wsdl: &WsdlDocument,
+    final_security_category_score: &mut u8,
+    tables: &mut Vec<ReportTable>,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let mut coverage = ReportTable::new(
+        "WS-Security policy coverage",
+        &["Binding", "Operation", "SOAP", "Assertions"],
+    );
+
+    let mut effective: Vec<(&Binding, OperationPolicies)> = Vec::new();
+    for binding in &wsdl.bindings {
+        let operations = binding
+            .operations
+            .iter()
+            .map(|operation| (operation, wsdl.operation_policy(binding, operation, None)))
+            .collect::<Vec<_>>();
+        for (operation, alternatives) in &operations {
+            coverage.add_row(vec![
+                binding.name.clone(),
+                operation.name.clone(),
+                binding
+                    .soap_version
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                describe(alternatives),
+            ]);
+        }
+        effective.push((binding, operations));
+    }
+
+    let secured_anywhere = effective
+        .iter()
+        .flat_map(|(_, operations)| operations.iter())
+        .flat_map(|(_, alternatives)| alternatives.iter())
+        .any(PolicyAssertions::is_secured);
+    let operation_count: usize = effective.iter().map(|(_, o)| o.len()).sum();
+    if operation_count > 0 && !secured_anywhere {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::NO_SECURITY_POLICY_VALUE,
+        );
+        fixables.push(Fixable::new(format!("None of the {} SOAP operation(s) has a WS-SecurityPolicy attached (`wsp:Policy` with `sp:TransportBinding`, `sp:UsernameToken`...). Nothing in the contract requires callers to authenticate or messages to be protected", operation_count), 0, WeightScore::High));
+        tables.push(coverage);
+        return fixables;
+    }
+
+    for (binding, operations) in &effective {
+        // operations sharing the same assertions are reported together
+        let mut groups: Vec<(&[PolicyAssertions], Vec<&BindingOperation>)> = Vec::new();
+        for (operation, alternatives) in operations {
+            match groups
+                .iter_mut()
+                .find(|(a, _)| *a == alternatives.as_slice())
+            {
+                Some((_, members)) => members.push(operation),
+                None => groups.push((alternatives, vec![operation])),
+            }
+        }
+        for (alternatives, members) in groups {
+            let subject = if members.len() == operations.len() && members.len() > 1 {
+                format!(
+                    "All {} operations of binding `{}`",
+                    members.len(),
+                    binding.name
+                )
+            } else {
+                let names: Vec<&str> = members.iter().map(|o| o.name.as_str()).collect();
+                format!(
+                    "Operation(s) {} of binding `{}`",
+                    names.join(", "),
+                    binding.name
+                )
+            };
+            let line = members.first().map(|o| o.line).unwrap_or(binding.line);
+            check_assertions(
+                &subject,
+                line,
+                alternatives,
+                final_security_category_score,
+                &mut fixables,
+            );
+        }
+    }
+
+    tables.push(coverage);
+    fixables
+}
+
+fn check_assertions(
+    subject: &str,
+    line: u64,
+    alternatives: &[PolicyAssertions],
+    final_security_category_score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    if !is_secured(alternatives) {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::OPERATION_WITHOUT_SECURITY_POLICY_VALUE,
+        );
+        if alternatives.iter().any(PolicyAssertions::is_secured) {
+            fixables.push(Fixable::new(format!("{} (line {}) have a policy alternative without security ({}). Callers can pick it and skip authentication and protection altogether, remove the unsecured alternative", subject, line, describe(alternatives)), line, WeightScore::High));
+        } else {
+            fixables.push(Fixable::new(format!("{} (line {}) have no security policy attached. Attach a WS-SecurityPolicy requiring authentication and transport or message protection", subject, line), line, WeightScore::High));
+        }
+        return;
+    }
+    let plain_text_password = alternatives
+        .iter()
+        .find(|a| a.username_token && !a.password_protected);
+    if let Some(assertions) = plain_text_password {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::PLAINTEXT_USERNAME_TOKEN_VALUE,
+        );
+        let weight = if assertions.transport_binding || assertions.encrypted_parts {
+            WeightScore::Medium
+        } else {
+            WeightScore::High
+        };
+        fixables.push(Fixable::new(format!("{} (line {}) require a UsernameToken without `sp:HashPassword`, so the password travels as plain text in the SOAP header. Hash it or use a certificate based token", subject, line), line, weight));
+    }
+    if alternatives.iter().any(|a| !a.include_timestamp) {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MISSING_TIMESTAMP_VALUE,
+        );
+        fixables.push(Fixable::new(format!("{} (line {}) don't require a security timestamp (`sp:IncludeTimestamp`). Captured messages can be replayed indefinitely", subject, line), line, WeightScore::Medium));
+    }
+    if alternatives
+        .iter()
+        .any(|a| !a.signed_parts && !a.transport_binding)
+    {
+        modify_score(
+            final_security_category_score,
+            IssueScoreImpact::MISSING_MESSAGE_SIGNATURE_VALUE,
+        );
+        fixables.push(Fixable::new(format!("{} (line {}) neither sign message parts (`sp:SignedParts`) nor rely on a `sp:TransportBinding`. Message integrity is not protected", subject, line), line, WeightScore::Medium));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECURE_POLICY: &str =
+        r#"<wsp:Policy><sp:TransportBinding/><sp:IncludeTimestamp/></wsp:Policy>"#;
+
+    /// Findings for a WSDL with `port_type`, `binding`, `service` and `port` inserted at the start
+    /// of the respective elements
+    fn findings(port_type: &str, binding: &str, service: &str, port: &str) -> Vec<String> {
+        let wsdl = WsdlDocument::parse(&format!(
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" xmlns:wsp="http://www.w3.org/ns/ws-policy" xmlns:sp="http://docs.oasis-open.org/ws-sx/ws-securitypolicy/200702" xmlns:tns="urn:orders" targetNamespace="urn:orders">
+  <portType name="Orders">{}<operation name="Place"/></portType>
+  <binding name="OrdersSoap" type="tns:Orders">{}
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place"/>
+  </binding>
+  <service name="OrderService">{}
+    <port name="OrdersPort" binding="tns:OrdersSoap">{}<soap:address location="https://orders.example.com/soap"/></port>
+  </service>
+</definitions>"#,
+            port_type, binding, service, port
+        ))
+        .unwrap();
+        check_ws_security(&wsdl, &mut 30, &mut vec![])
+            .iter()
+            .map(Fixable::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn operations_without_any_policy_are_reported() {
+        let findings = findings("", "", "", "");
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].contains("None of the 1 SOAP operation(s) has a WS-SecurityPolicy"));
+    }
+
+    #[test]
+    fn policies_of_the_port_type_service_and_port_apply_to_the_operations() {
+        for attachment in [
+            findings(SECURE_POLICY, "", "", ""),
+            findings("", SECURE_POLICY, "", ""),
+            findings("", "", SECURE_POLICY, ""),
+            findings("", "", "", SECURE_POLICY),
+        ] {
+            assert!(attachment.is_empty(), "{:?}", attachment);
+        }
+    }
+
+    #[test]
+    fn an_unsecured_policy_alternative_is_reported() {
+        let optional = r#"<wsp:Policy><wsp:ExactlyOne><wsp:All><sp:TransportBinding/><sp:IncludeTimestamp/></wsp:All><wsp:All/></wsp:ExactlyOne></wsp:Policy>"#;
+        let findings = findings("", optional, "", "");
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].contains("have a policy alternative without security"));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::wsdl::schema_origin;
+use crate::rules::{capitalise, name_tokens};
+use crate::wsdl::schema::{
+    is_xsd_string, AttributeDecl, ElementDecl, Facets, SchemaSet, SimpleType,
+};
+use crate::wsdl::xml::QName;
+use crate::wsdl::WsdlDocument;
+
+/// Last words of field names holding numbers, money or quantities which a plain string can't
+/// validate
+const NUMERIC_NAME_WORDS: [&str; 12] = [
+    "amount", "price", "total", "balance", "quantity", "qty", "fee", "charge", "rate", "units",
+    "payable", "cost",
+];
+
+/// XSD analogue of the OpenAPI data validation checks: unbounded repetitions, strings without
+/// length or pattern facets and numeric looking fields typed as strings.
+pub fn check_schema_constraints(
+    wsdl: &WsdlDocument,
+    final_data_validation_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    let checker = ConstraintChecker {
+        schemas: &wsdl.schemas,
+    };
+
+    for schema in &wsdl.schemas.schemas {
+        let namespace = &schema.target_namespace;
+        let origin = schema_origin(schema);
+        let globals: Vec<ElementDecl> = schema
+            .elements
+            .iter()
+            .filter(|element| element.complex_type.is_none())
+            .cloned()
+            .collect();
+        checker.check_container(
+            &format!("schema `{}`{}", namespace, origin),
+            schema.line,
+            &globals,
+            &[],
+            final_data_validation_category_score,
+            &mut fixables,
+        );
+        for element in &schema.elements {
+            if let Some(complex_type) = &element.complex_type {
+                checker.check_container(
+                    &format!("element `{}` of {}{}", element.name, namespace, origin),
+                    element.line,
+                    &complex_type.elements,
+                    &complex_type.attributes,
+                    final_data_validation_category_score,
+                    &mut fixables,
+                );
+            }
+        }
+        for complex_type in &schema.complex_types {
+            checker.check_container(
+                &format!(
+                    "complex type `{}` of {}{}",
+                    complex_type.name.as_deref().unwrap_or_default(),
+                    namespace,
+                    origin
+                ),
+                complex_type.line,
+                &complex_type.elements,
+                &complex_type.attributes,
+                final_data_validation_category_score,
+                &mut fixables,
+            );
+        }
+    }
+
+    fixables
+}
+
+struct ConstraintChecker<'a> {
+    schemas: &'a SchemaSet,
+}
+
+impl<'a> ConstraintChecker<'a> {
+    /// Check the particles and attributes declared by one global element, complex type or
+    /// schema. Findings are grouped per container to keep large schemas readable.
+    fn check_container(
+        &self,
+        container: &str,
+        line: u64,
+        elements: &[ElementDecl],
+        attributes: &[AttributeDecl],
+        final_data_validation_category_score: &mut u8,
+        fixables: &mut Vec<Fixable>,
+    ) {
+        let mut unconstrained: Vec<&str> = Vec::new();
+        let mut numeric: Vec<&str> = Vec::new();
+
+        for element in elements {
+            if element.max_occurs.is_none() {
+                modify_score(
+                    final_data_validation_category_score,
+                    IssueScoreImpact::ARRAY_WITHOUT_MAX_ITEMS_VALUE,
+                );
+                fixables.push(Fixable::new(format!("Element `{}` of {} (line {}) has `maxOccurs=\"unbounded\"`. Requests can repeat it without limit, set a maximum the service can handle", element.name, container, element.line), element.line, WeightScore::High));
+            }
+            if let Some(complex_type) = &element.complex_type {
+                self.check_container(
+                    &format!("element `{}` of {}", element.name, container),
+                    element.line,
+                    &complex_type.elements,
+                    &complex_type.attributes,
+                    final_data_validation_category_score,
+                    fixables,
+                );
+            }
+            // referenced global elements are checked where they're declared
+            if element.reference.is_some() {
+                continue;
+            }
+            let facets = match &element.simple_type {
+                Some(simple_type) => {
+                    self.string_facets(simple_type.base.as_ref(), Some(simple_type))
+                }
+                None => self.string_facets(element.type_name.as_ref(), None),
+            };
+            if let Some(facets) = facets {
+                self.classify(&element.name, &facets, &mut unconstrained, &mut numeric);
+            }
+        }
+        for attribute in attributes {
+            let facets = match &attribute.simple_type {
+                Some(simple_type) => {
+                    self.string_facets(simple_type.base.as_ref(), Some(simple_type))
+                }
+                None => self.string_facets(attribute.type_name.as_ref(), None),
+            };
+            if let Some(facets) = facets {
+                self.classify(&attribute.name, &facets, &mut unconstrained, &mut numeric);
+            }
+        }
+
+        if !unconstrained.is_empty() {
+            modify_score(
+                final_data_validation_category_score,
+                IssueScoreImpact::STRING_PROPERTY_WITHOUT_MAX_LENGTH_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} (line {}) declares strings without `maxLength` or `pattern` facets: {}. Any length and content is accepted, restrict them with facets", capitalise(container), line, unconstrained.join(", ")), line, WeightScore::Medium));
+        }
+        if !numeric.is_empty() {
+            modify_score(
+                final_data_validation_category_score,
+                IssueScoreImpact::NUMERIC_VALUE_TYPED_AS_STRING_VALUE,
+            );
+            fixables.push(Fixable::new(format!("{} (line {}) types numeric looking fields as strings: {}. Use `decimal`/`int` (or a numeric `pattern`) so malformed amounts are rejected by schema validation", capitalise(container), line, numeric.join(", ")), line, WeightScore::Medium));
+        }
+    }
+
+    /// Facets of a string typed declaration, `None` when it isn't a string at all
+    fn string_facets(
+        &self,
+        type_name: Option<&QName>,
+        inline: Option<&SimpleType>,
+    ) -> Option<Facets> {
+        let declared = match inline {
+            Some(simple_type) => Some(simple_type),
+            None => type_name.and_then(|name| self.schemas.simple_type(name)),
+        };
+        match declared {
+            Some(simple_type) => {
+                let (base, facets) = self.schemas.restriction_of(simple_type);
+                base.filter(is_xsd_string).map(|_| facets)
+            }
+            None => type_name
+                .filter(|name| is_xsd_string(name))
+                .map(|_| Facets::default()),
+        }
+    }
+
+    fn classify<'n>(
+        &self,
+        name: &'n str,
+        facets: &Facets,
+        unconstrained: &mut Vec<&'n str>,
+        numeric: &mut Vec<&'n str>,
+    ) {
+        if facets.pattern.is_some() || !facets.enumeration.is_empty() {
+            return;
+        }
+        if is_numeric_name(name) {
+            numeric.push(name);
+        }
+        if facets.max_length.is_none() && facets.length.is_none() {
+            unconstrained.push(name);
+        }
+    }
+}
+
+/// Only the last word counts (`totalAmount`, `unit_price`), so `Corporate` or `Coffee` aren't
+/// numeric
+fn is_numeric_name(name: &str) -> bool {
+    name_tokens(name).last().is_some_and(|word| {
+        NUMERIC_NAME_WORDS
+            .iter()
+            .any(|numeric| word == numeric || word.strip_suffix('s') == Some(*numeric))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_ending_in_a_numeric_word_are_numeric() {
+        for name in [
+            "Amount",
+            "totalAmount",
+            "unit_price",
+            "Fees",
+            "ExchangeRate",
+            "qty",
+        ] {
+            assert!(is_numeric_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn names_merely_ending_in_numeric_letters_are_not_numeric() {
+        for name in [
+            "Corporate",
+            "Generate",
+            "Coffee",
+            "Separate",
+            "AmountType",
+            "Totally",
+        ] {
+            assert!(!is_numeric_name(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::openapi::{Fixable, IssueScoreImpact, WeightScore};
+use crate::parser::modify_score;
+use crate::rules::capitalise;
+use crate::rules::wsdl::schema_origin;
+use crate::wsdl::dtd::Doctype;
+use crate::wsdl::schema::{ComplexType, ElementDecl, Wildcard};
+use crate::wsdl::WsdlDocument;
+
+/// XML injection through the schemas: wildcards letting arbitrary XML through and mixed content.
+pub fn check_xml_attack_surface(
+    wsdl: &WsdlDocument,
+    final_security_category_score: &mut u8,
+) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+
+    for schema in &wsdl.schemas.schemas {
+        let mut complex_types: Vec<(String, &ComplexType)> = Vec::new();
+        for complex_type in &schema.complex_types {
+            let name = complex_type.name.as_deref().unwrap_or_default();
+            complex_types.push((format!("complex type `{}`", name), complex_type));
+            collect_inline_types(&complex_type.elements, &mut complex_types);
+        }
+        collect_inline_types(&schema.elements, &mut complex_types);
+
+        for (label, complex_type) in &complex_types {
+            for wildcard in &complex_type.any_elements {
+                check_wildcard(
+                    label,
+                    "elements",
+                    wildcard,
+                    final_security_category_score,
+                    &mut fixables,
+                );
+            }
+            if let Some(wildcard) = &complex_type.any_attribute {
+                check_wildcard(
+                    label,
+                    "attributes",
+                    wildcard,
+                    final_security_category_score,
+                    &mut fixables,
+                );
+            }
+        }
+
+        let mixed: Vec<&(String, &ComplexType)> =
+            complex_types.iter().filter(|(_, t)| t.mixed).collect();
+        if let Some((_, first)) = mixed.first() {
+            modify_score(
+                final_security_category_score,
+                IssueScoreImpact::MIXED_CONTENT_VALUE,
+            );
+            let labels: Vec<String> = mixed
+                .iter()
+                .map(|(label, t)| format!("{} (line {})", label, t.line))
+                .collect();
+            fixables.push(Fixable::new(format!("Schema `{}`{} (line {}) allows mixed content (`mixed=\"true\"`) in {}. Free text can be interleaved with the declared elements without any validation, an XML injection vector, drop `mixed` unless text content is really expected", schema.target_namespace, schema_origin(schema), schema.line, labels.join(", ")), first.line, WeightScore::Medium));
+        }
+    }
+
+    fixables
+}
+
+/// XXE and entity expansion: the DOCTYPE declaration, external DTD and external or nested
+/// entities. Runs on the raw text, documents using their entities don't parse.
+pub fn check_doctype(doctype: &Doctype, score: &mut u8) -> Vec<Fixable> {
+    let mut fixables: Vec<Fixable> = Vec::new();
+    modify_score(score, IssueScoreImpact::XML_DOCTYPE_DECLARATION_VALUE);
+    fixables.push(Fixable::new(format!("Document declares a DOCTYPE (line {}). Consumers whose XML parser processes DTDs are exposed to XML external entity (XXE) and entity expansion attacks, remove the declaration: WSDL and XSD don't need one", doctype.line), doctype.line, WeightScore::Medium));
+
+    if let Some(system_id) = &doctype.system_id {
+        modify_score(score, IssueScoreImpact::XML_EXTERNAL_ENTITY_VALUE);
+        fixables.push(Fixable::new(format!("DOCTYPE (line {}) loads an external DTD from `{}`. Parsers fetching it can be made to read local files or reach internal hosts (XXE), remove the reference", doctype.line, system_id), doctype.line, WeightScore::High));
+    }
+    for entity in &doctype.entities {
+        let kind = if entity.parameter {
+            "Parameter entity"
+        } else {
+            "Entity"
+        };
+        if let Some(system_id) = &entity.system_id {
+            modify_score(score, IssueScoreImpact::XML_EXTERNAL_ENTITY_VALUE);
+            fixables.push(Fixable::new(format!("{} `{}` (line {}) is an external entity resolved from `{}`. Parsers expanding it disclose local files or make requests to internal hosts (XXE), remove it", kind, entity.name, entity.line, system_id), entity.line, WeightScore::Critical));
+        } else if entity.is_nested() {
+            modify_score(score, IssueScoreImpact::XML_ENTITY_EXPANSION_VALUE);
+            fixables.push(Fixable::new(format!("{} `{}` (line {}) expands to other entities. Nested entities grow exponentially when expanded (\"billion laughs\") and exhaust the parser's memory, remove them", kind, entity.name, entity.line), entity.line, WeightScore::High));
+        }
+    }
+    fixables
+}
+
+fn check_wildcard(
+    label: &str,
+    accepted: &str,
+    wildcard: &Wildcard,
+    score: &mut u8,
+    fixables: &mut Vec<Fixable>,
+) {
+    let weight = match wildcard.process_contents.as_str() {
+        "skip" => WeightScore::High,
+        "lax" => WeightScore::Medium,
+        _ => return,
+    };
+    modify_score(score, IssueScoreImpact::XML_WILDCARD_CONTENT_VALUE);
+    fixables.push(Fixable::new(format!("{} (line {}) accepts any {} from `{}` with `processContents=\"{}\"`. Arbitrary XML reaches the service unvalidated, an XML injection vector, declare the expected content or use `processContents=\"strict\"`", capitalise(label), wildcard.line, accepted, wildcard.namespace, wildcard.process_contents), wildcard.line, weight));
+}
+
+/// Anonymous complex types declared inline by `elements` and their descendants
+fn collect_inline_types<'a>(
+    elements: &'a [ElementDecl],
+    complex_types: &mut Vec<(String, &'a ComplexType)>,
+) {
+    for element in elements {
+        if let Some(complex_type) = &element.complex_type {
+            complex_types.push((format!("element `{}`", element.name), complex_type));
+            collect_inline_types(&complex_type.elements, complex_types);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Entities every XML parser knows without a DTD
+const PREDEFINED_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+
+/// An `<!ENTITY>` declaration of the internal DTD subset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDecl {
+    pub name: String,
+    /// `<!ENTITY % name ...>`, only usable inside the DTD
+    pub parameter: bool,
+    /// URI of an external (`SYSTEM`/`PUBLIC`) entity
+    pub system_id: Option<String>,
+    /// replacement text of an internal entity
+    pub value: Option<String>,
+    pub line: u64,
+}
+
+impl EntityDecl {
+    /// Whether the replacement text references other entities, the building block of
+    /// exponential entity expansion ("billion laughs")
+    pub fn is_nested(&self) -> bool {
+        let reference = Regex::new(r"[&%]([A-Za-z_][\w.-]*);").unwrap();
+        self.value
+            .as_deref()
+            .map(|value| {
+                reference
+                    .captures_iter(value)
+                    .any(|captures| !PREDEFINED_ENTITIES.contains(&&captures[1]))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// The `<!DOCTYPE>` declaration of a document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Doctype {
+    /// URI of the external DTD subset, `<!DOCTYPE root SYSTEM "...">`
+    pub system_id: Option<String>,
+    pub entities: Vec<EntityDecl>,
+    pub line: u64,
+    /// byte range of the whole declaration in the document
+    pub span: (usize, usize),
+}
+
+/// Find the `<!DOCTYPE>` of the prolog, reading the text itself as the XML parser skips it.
+pub fn find_doctype(text: &str) -> Option<Doctype> {
+    let start = prolog_doctype_start(text)?;
+    let declaration = &text[start..];
+
+    // the internal subset may contain `>`, the declaration ends after its closing `]`
+    let subset = declaration
+        .find(['[', '>'])
+        .filter(|&i| declaration.as_bytes()[i] == b'[');
+    let end = match subset {
+        Some(open) => {
+            let close = open + subset_end(&declaration[open..])?;
+            close + declaration[close..].find('>')? + 1
+        }
+        None => declaration.find('>')? + 1,
+    };
+    let declaration = &declaration[..end];
+
+    let external = Regex::new(
+        r#"^<!DOCTYPE\s+[^\s\[>]+\s+(?:SYSTEM|PUBLIC\s+(?:"[^"]*"|'[^']*'))\s*("[^"]*"|'[^']*')"#,
+    )
+    .unwrap();
+    let system_id = external
+        .captures(declaration)
+        .map(|captures| unquote(&captures[1]));
+
+    let entity = Regex::new(r#"<!ENTITY\s+(%\s+)?([^\s>]+)\s+(?:(?:SYSTEM|PUBLIC\s+(?:"[^"]*"|'[^']*'))\s*("[^"]*"|'[^']*')|("[^"]*"|'[^']*'))"#).unwrap();
+    let entities = match subset {
+        Some(open) => entity
+            .captures_iter(declaration)
+            .filter(|captures| captures.get(0).unwrap().start() > open)
+            .map(|captures| EntityDecl {
+                name: captures[2].to_string(),
+                parameter: captures.get(1).is_some(),
+                system_id: captures.get(3).map(|id| unquote(id.as_str())),
+                value: captures.get(4).map(|value| unquote(value.as_str())),
+                line: line_of(text, start + captures.get(0).unwrap().start()),
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    Some(Doctype {
+        system_id,
+        entities,
+        line: line_of(text, start),
+        span: (start, start + end),
+    })
+}
+
+/// Offset of the `<!DOCTYPE`, skipping the XML declaration, processing instructions and comments
+/// before the root element
+fn prolog_doctype_start(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    loop {
+        let rest = &text[offset..];
+        let next = offset + rest.find('<')?;
+        let markup = &text[next..];
+        if markup.starts_with("<!DOCTYPE") {
+            return Some(next);
+        } else if markup.starts_with("<!--") {
+            offset = next + markup.find("-->")? + 3;
+        } else if markup.starts_with("<?") {
+            offset = next + markup.find("?>")? + 2;
+        } else {
+            // the root element, no DOCTYPE can follow
+            return None;
+        }
+    }
+}
+
+/// Offset of the `]` closing the internal subset starting at `subset[0]`. Entity values,
+/// system literals, comments and processing instructions may contain `]` and are skipped.
+fn subset_end(subset: &str) -> Option<usize> {
+    let mut offset = 1;
+    loop {
+        let rest = &subset[offset..];
+        let next = offset + rest.find([']', '"', '\'', '<'])?;
+        let markup = &subset[next..];
+        offset = if markup.starts_with(']') {
+            return Some(next);
+        } else if markup.starts_with('"') || markup.starts_with('\'') {
+            let quote = &markup[..1];
+            next + 1 + markup[1..].find(quote)? + 1
+        } else if markup.starts_with("<!--") {
+            next + markup.find("-->")? + 3
+        } else if markup.starts_with("<?") {
+            next + markup.find("?>")? + 2
+        } else {
+            next + 1
+        };
+    }
+}
+
+fn unquote(literal: &str) -> String {
+    literal[1..literal.len() - 1].to_string()
+}
+
+fn line_of(text: &str, offset: usize) -> u64 {
+    text[..offset].matches('\n').count() as u64 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_subset_ends_at_its_own_closing_bracket() {
+        let text = r#"<?xml version="1.0"?>
+<!DOCTYPE definitions [
+  <!-- a ] in a comment -->
+  <!ENTITY x "a]b">
+  <!ENTITY y SYSTEM 'file:///etc/]passwd'>
+]>
+<definitions/>"#;
+        let doctype = find_doctype(text).unwrap();
+        assert_eq!(&text[doctype.span.1..], "\n<definitions/>");
+        let names: Vec<&str> = doctype.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["x", "y"]);
+        assert_eq!(doctype.entities[0].value.as_deref(), Some("a]b"));
+        assert_eq!(
+            doctype.entities[1].system_id.as_deref(),
+            Some("file:///etc/]passwd")
+        );
+        assert_eq!(doctype.entities[1].line, 5);
+    }
+
+    #[test]
+    fn nested_entities_ignore_predefined_ones() {
+        let text = r#"<!DOCTYPE d [<!ENTITY a "&amp;"><!ENTITY b "&a;&a;">]><d/>"#;
+        let doctype = find_doctype(text).unwrap();
+        assert!(!doctype.entities[0].is_nested());
+        assert!(doctype.entities[1].is_nested());
+    }
+}
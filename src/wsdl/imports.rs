@@ -0,0 +1,445 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::wsdl::schema::Schema;
+use crate::wsdl::xml::{parse_document, read_document, XmlElement};
+use crate::wsdl::{WsdlDocument, WSDL_11_NS, WSDL_20_NS, XSD_NS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportKind {
+    /// `wsdl:import`/`wsdl:include`
+    Wsdl,
+    /// `xs:import`
+    SchemaImport,
+    /// `xs:include`/`xs:redefine`
+    SchemaInclude,
+}
+impl std::fmt::Display for ImportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportKind::Wsdl => write!(f, "WSDL import"),
+            ImportKind::SchemaImport => write!(f, "schema import"),
+            ImportKind::SchemaInclude => write!(f, "schema include"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportStatus {
+    /// loaded from (or already loaded from) the file
+    Resolved(PathBuf),
+    /// not loaded, with the reason
+    Unresolved(String),
+    /// the file is already being imported further up the chain
+    Cycle(PathBuf),
+}
+
+/// An import or include met while loading the document, and what became of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Import {
+    pub kind: ImportKind,
+    pub location: String,
+    /// file the reference is in, `None` for the scanned document itself
+    pub origin: Option<PathBuf>,
+    pub status: ImportStatus,
+    pub line: u64,
+}
+
+/// Resolves imports and includes from local files only, nothing is ever downloaded:
+/// relative locations against the importing file, absolute URLs against a catalogue directory
+/// holding copies of the remote documents.
+#[derive(Debug, Clone, Default)]
+pub struct ImportResolver {
+    /// path of the scanned document
+    input: Option<PathBuf>,
+    catalogue: Option<PathBuf>,
+}
+
+impl ImportResolver {
+    pub fn new(input: Option<PathBuf>, catalogue: Option<PathBuf>) -> Self {
+        Self { input, catalogue }
+    }
+
+    /// Load everything `wsdl` imports, transitively, and merge it into the document.
+    /// <br>Failures don't abort the scan, they end up in `wsdl.imports`.
+    pub fn resolve(&self, wsdl: &mut WsdlDocument) {
+        let input = self.input.as_ref().map(|path| canonical(path));
+        let mut loader = Loader {
+            resolver: self,
+            loading: input.iter().cloned().collect(),
+            loaded: vec![],
+            imports: vec![],
+            schemas: vec![],
+            documents: vec![],
+        };
+        loader.follow(&wsdl.root, input.as_deref(), &wsdl.target_namespace);
+
+        wsdl.schemas.schemas.extend(loader.schemas);
+        for document in loader.documents {
+            wsdl.schemas.schemas.extend(document.schemas.schemas);
+            wsdl.messages.extend(document.messages);
+            wsdl.port_types.extend(document.port_types);
+            wsdl.bindings.extend(document.bindings);
+            wsdl.services.extend(document.services);
+            wsdl.policies.extend(document.policies);
+        }
+        wsdl.imports = loader.imports;
+    }
+
+    /// The local file `location` stands for, seen from the file `origin`, canonicalised.
+    /// <br>Locations never leave the directory they are resolved against: absolute paths,
+    /// `file://` URLs and `..` components are refused.
+    fn locate(&self, location: &str, origin: Option<&Path>) -> Result<PathBuf, String> {
+        if let Some(url) = location
+            .strip_prefix("http://")
+            .or_else(|| location.strip_prefix("https://"))
+        {
+            let Some(catalogue) = &self.catalogue else {
+                return Err("remote location and no catalogue directory was given".to_string());
+            };
+            let url = url.split(['?', '#']).next().unwrap_or_default();
+            // a mirror of the URL (`host/path`) first, then the bare file name
+            let mirrored = contained(catalogue, url)?;
+            let flat = match url.rsplit('/').next() {
+                Some(name) if !name.is_empty() => Some(contained(catalogue, name)?),
+                _ => None,
+            };
+            return [Some(mirrored), flat]
+                .into_iter()
+                .flatten()
+                .find(|path| path.is_file())
+                .ok_or_else(|| format!("not found in the catalogue {}", catalogue.display()));
+        }
+
+        if location.starts_with("file:") || Path::new(location).is_absolute() {
+            return Err(
+                "absolute location, only locations relative to the importing file are followed"
+                    .to_string(),
+            );
+        }
+        match origin.and_then(Path::parent) {
+            Some(directory) => contained(directory, location),
+            None => {
+                Err("relative location but the path of the scanned file is unknown".to_string())
+            }
+        }
+    }
+}
+
+/// `relative` resolved against the directory `base`, refused if it would end up outside of it
+fn contained(base: &Path, relative: &str) -> Result<PathBuf, String> {
+    let escapes = Path::new(relative)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(format!(
+            "`{}` is not a plain relative path, it could leave {}",
+            relative,
+            base.display()
+        ));
+    }
+    let base = canonical(base);
+    // canonical resolves symbolic links, which may point anywhere
+    let path = canonical(&base.join(relative));
+    if !path.starts_with(&base) {
+        return Err(format!(
+            "{} is outside of {}",
+            path.display(),
+            base.display()
+        ));
+    }
+    Ok(path)
+}
+
+struct Loader<'r> {
+    resolver: &'r ImportResolver,
+    /// files on the current import chain
+    loading: Vec<PathBuf>,
+    loaded: Vec<PathBuf>,
+    imports: Vec<Import>,
+    schemas: Vec<Schema>,
+    documents: Vec<WsdlDocument>,
+}
+
+impl Loader<'_> {
+    /// Load the imports and includes of a WSDL or schema document
+    fn follow(&mut self, root: &XmlElement, origin: Option<&Path>, target_namespace: &str) {
+        // kind, element, location attribute and namespace of the document making the reference
+        let mut references: Vec<(ImportKind, &XmlElement, &str, &str)> = Vec::new();
+        let mut schemas: Vec<&XmlElement> = Vec::new();
+        for wsdl_namespace in [WSDL_11_NS, WSDL_20_NS] {
+            for child in root
+                .children
+                .iter()
+                .filter(|c| c.namespace == wsdl_namespace)
+            {
+                if child.name == "import" || child.name == "include" {
+                    references.push((ImportKind::Wsdl, child, "location", target_namespace));
+                }
+            }
+            schemas.extend(
+                root.children_named(wsdl_namespace, "types")
+                    .flat_map(|types| types.children_named(XSD_NS, "schema")),
+            );
+        }
+        if root.is(XSD_NS, "schema") {
+            schemas.push(root);
+        }
+        for schema in &schemas {
+            let namespace = schema.attr("targetNamespace").unwrap_or(target_namespace);
+            for child in schema.children.iter().filter(|c| c.namespace == XSD_NS) {
+                match child.name.as_str() {
+                    "import" => references.push((
+                        ImportKind::SchemaImport,
+                        child,
+                        "schemaLocation",
+                        namespace,
+                    )),
+                    "include" | "redefine" => references.push((
+                        ImportKind::SchemaInclude,
+                        child,
+                        "schemaLocation",
+                        namespace,
+                    )),
+                    _ => {}
+                }
+            }
+        }
+
+        for (kind, reference, attribute, namespace) in references {
+            // a namespace only `xs:import` refers to a schema known by other means
+            let Some(location) = reference.attr(attribute) else {
+                continue;
+            };
+            let status = self.load(kind, location, origin, namespace);
+            self.imports.push(Import {
+                kind,
+                location: location.to_string(),
+                origin: origin.map(Path::to_path_buf),
+                status,
+                line: reference.line,
+            });
+        }
+    }
+
+    fn load(
+        &mut self,
+        kind: ImportKind,
+        location: &str,
+        origin: Option<&Path>,
+        including_namespace: &str,
+    ) -> ImportStatus {
+        let path = match self.resolver.locate(location, origin) {
+            Ok(path) => path,
+            Err(reason) => return ImportStatus::Unresolved(reason),
+        };
+        if self.loading.contains(&path) {
+            return ImportStatus::Cycle(path);
+        }
+        if self.loaded.contains(&path) {
+            return ImportStatus::Resolved(path);
+        }
+        let text = match read_document(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                return ImportStatus::Unresolved(format!("cannot read {}, {}", path.display(), e))
+            }
+        };
+
+        self.loading.push(path.clone());
+        let loaded = match kind {
+            ImportKind::Wsdl => WsdlDocument::parse(&text)
+                .map(|document| {
+                    self.follow(&document.root, Some(&path), &document.target_namespace);
+                    self.documents.push(document);
+                })
+                .map_err(|e| format!("not a valid WSDL document: {}", e)),
+            ImportKind::SchemaImport | ImportKind::SchemaInclude => parse_document(&text)
+                .map_err(|e| format!("not a valid XML document: {}", e))
+                .and_then(|root| {
+                    if !root.is(XSD_NS, "schema") {
+                        return Err("not an XML schema".to_string());
+                    }
+                    let mut schema = Schema::parse(&root);
+                    // an included schema without namespace takes the one of the schema including it
+                    if kind == ImportKind::SchemaInclude && schema.target_namespace.is_empty() {
+                        schema.target_namespace = including_namespace.to_string();
+                    }
+                    schema.location = Some(path.display().to_string());
+                    let namespace = schema.target_namespace.clone();
+                    self.follow(&root, Some(&path), &namespace);
+                    self.schemas.push(schema);
+                    Ok(())
+                }),
+        };
+        self.loading.pop();
+
+        match loaded {
+            Ok(()) => {
+                self.loaded.push(path.clone());
+                ImportStatus::Resolved(path)
+            }
+            Err(reason) => ImportStatus::Unresolved(reason),
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::xml::QName;
+
+    /// A scratch directory holding `files`, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let directory = std::env::temp_dir().join(format!(
+                "rustysec-imports-{}-{}",
+                name,
+                std::process::id()
+            ));
+            for (path, content) in files {
+                let path = directory.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+            Self(directory)
+        }
+
+        fn resolve(&self, main: &str) -> WsdlDocument {
+            let path = self.0.join(main);
+            let mut wsdl = WsdlDocument::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+            let catalogue = path.parent().unwrap().join("catalogue");
+            ImportResolver::new(Some(path), Some(catalogue)).resolve(&mut wsdl);
+            wsdl
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn wsdl_importing(locations: &[&str]) -> String {
+        let imports: String = locations
+            .iter()
+            .map(|location| format!(r#"<import namespace="urn:other" location="{}"/>"#, location))
+            .collect();
+        format!(
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:main">{}</definitions>"#,
+            imports
+        )
+    }
+
+    #[test]
+    fn imported_definitions_resolve_in_their_own_namespace() {
+        let scratch = Scratch::new(
+            "resolved",
+            &[
+                (
+                    "main.wsdl",
+                    &wsdl_importing(&["other.wsdl", "http://example.com/remote.wsdl"]),
+                ),
+                (
+                    "other.wsdl",
+                    r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:other">
+  <message name="Order"/>
+  <binding name="OrderBinding"/>
+</definitions>"#,
+                ),
+                (
+                    "catalogue/example.com/remote.wsdl",
+                    r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:remote"/>"#,
+                ),
+            ],
+        );
+        let wsdl = scratch.resolve("main.wsdl");
+
+        assert!(wsdl
+            .imports
+            .iter()
+            .all(|import| matches!(import.status, ImportStatus::Resolved(_))));
+        assert!(wsdl.message(&QName::new("urn:other", "Order")).is_some());
+        assert!(wsdl
+            .binding(&QName::new("urn:other", "OrderBinding"))
+            .is_some());
+        assert!(wsdl.message(&QName::new("urn:main", "Order")).is_none());
+    }
+
+    #[test]
+    fn missing_imports_are_unresolved() {
+        let scratch = Scratch::new(
+            "missing",
+            &[("main.wsdl", &wsdl_importing(&["missing.wsdl"]))],
+        );
+        let wsdl = scratch.resolve("main.wsdl");
+
+        assert_eq!(wsdl.imports.len(), 1);
+        assert!(matches!(
+            wsdl.imports[0].status,
+            ImportStatus::Unresolved(_)
+        ));
+    }
+
+    #[test]
+    fn imports_back_onto_the_chain_are_cycles() {
+        let scratch = Scratch::new(
+            "cycle",
+            &[
+                ("main.wsdl", &wsdl_importing(&["a.wsdl"])),
+                ("a.wsdl", &wsdl_importing(&["main.wsdl"])),
+            ],
+        );
+        let wsdl = scratch.resolve("main.wsdl");
+
+        let cycles: Vec<&Import> = wsdl
+            .imports
+            .iter()
+            .filter(|import| matches!(import.status, ImportStatus::Cycle(_)))
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].location, "main.wsdl");
+    }
+
+    #[test]
+    fn locations_leaving_their_directory_are_refused() {
+        let escapes = [
+            "../outside.wsdl",
+            "nested/../../outside.wsdl",
+            "/etc/passwd",
+            "file:///etc/passwd",
+            "http:///etc/passwd",
+            "http://example.com/../../outside.wsdl",
+        ];
+        let scratch = Scratch::new(
+            "traversal",
+            &[
+                ("service/main.wsdl", &wsdl_importing(&escapes)),
+                ("service/catalogue/.keep", ""),
+                (
+                    "outside.wsdl",
+                    r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:other"/>"#,
+                ),
+            ],
+        );
+        let wsdl = scratch.resolve("service/main.wsdl");
+
+        assert_eq!(wsdl.imports.len(), escapes.len());
+        for import in &wsdl.imports {
+            assert!(
+                matches!(import.status, ImportStatus::Unresolved(_)),
+                "{}: {:?}",
+                import.location,
+                import.status
+            );
+        }
+    }
+}
@@ -0,0 +1,854 @@
+//! WSDL (SOAP) service descriptions.
+//!
+//! The document is parsed into a namespace aware element tree first ([`xml`]), the pieces
+//! the rules care about are then lifted into a model: the XSD schemas of `types` ([`schema`]),
+//! the messages, port types and bindings, the WS-Policy assertions attached to them
+//! ([`policy`]) and the services.
+//!
+//! WSDL 2.0 descriptions are mapped into the same model: interfaces become port types, their
+//! operations' message elements become single part messages and endpoints become ports.
+
+pub mod dtd;
+pub mod imports;
+pub mod normalise;
+pub mod policy;
+pub mod sample;
+pub mod schema;
+pub mod xml;
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::ParserError;
+use crate::wsdl::dtd::{find_doctype, Doctype};
+use crate::wsdl::imports::Import;
+use crate::wsdl::policy::{
+    attached_policies, combine, is_policy_element, parse_policy, Policy, PolicyAssertions,
+    PolicyAttachment,
+};
+use crate::wsdl::schema::{Schema, SchemaSet};
+use crate::wsdl::xml::{parse_document, root_name, QName, XmlElement};
+
+pub const WSDL_11_NS: &str = "http://schemas.xmlsoap.org/wsdl/";
+pub const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+pub const SOAP_11_BINDING_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+pub const SOAP_12_BINDING_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+pub const WSDL_20_NS: &str = "http://www.w3.org/ns/wsdl";
+/// WSDL 2.0 SOAP binding extension, also the `type` of SOAP bindings
+pub const WSDL_20_SOAP_NS: &str = "http://www.w3.org/ns/wsdl/soap";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WsdlVersion {
+    /// `definitions` root
+    Wsdl11,
+    /// `description` root
+    Wsdl20,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SoapVersion {
+    Soap11,
+    Soap12,
+}
+impl std::fmt::Display for SoapVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SoapVersion::Soap11 => write!(f, "SOAP 1.1"),
+            SoapVersion::Soap12 => write!(f, "SOAP 1.2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessagePart {
+    pub name: String,
+    /// global element of the part, document style
+    pub element: Option<QName>,
+    /// type of the part, rpc style
+    pub type_name: Option<QName>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub name: String,
+    /// target namespace of the definitions declaring the message, which may be an imported one
+    pub namespace: String,
+    pub parts: Vec<MessagePart>,
+    pub line: u64,
+}
+
+/// An abstract operation of a port type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortTypeOperation {
+    pub name: String,
+    pub input: Option<QName>,
+    pub output: Option<QName>,
+    pub faults: Vec<QName>,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortType {
+    pub name: String,
+    pub operations: Vec<PortTypeOperation>,
+    /// policies attached to the port type, they apply wherever it is bound
+    pub policies: Vec<PolicyAttachment>,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingOperation {
+    pub name: String,
+    pub soap_action: Option<String>,
+    pub style: Option<String>,
+    /// `namespace` of the input's `soap:body`, wraps the parts of rpc style operations
+    pub input_namespace: Option<String>,
+    /// `parts` of the input's `soap:body`, `None` when every part goes in the body
+    pub input_body_parts: Option<Vec<String>>,
+    /// message parts the input carries in the SOAP header (`soap:header`)
+    pub input_headers: Vec<SoapHeader>,
+    /// policies attached to the operation and to its input, output and faults
+    pub policies: Vec<PolicyAttachment>,
+    pub line: u64,
+}
+
+/// A `soap:header` of a bound input or output: the message part going in the SOAP header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoapHeader {
+    pub message: QName,
+    pub part: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binding {
+    pub name: String,
+    /// target namespace of the definitions declaring the binding, which may be an imported one
+    pub namespace: String,
+    pub port_type: Option<QName>,
+    /// `None` for bindings that aren't SOAP bindings (e.g. HTTP GET/POST)
+    pub soap_version: Option<SoapVersion>,
+    pub transport: Option<String>,
+    /// default style of the binding's operations, `document` or `rpc`
+    pub style: Option<String>,
+    pub policies: Vec<PolicyAttachment>,
+    pub operations: Vec<BindingOperation>,
+    pub line: u64,
+}
+
+/// A `port` of a service: a binding made available at an address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Port {
+    pub name: String,
+    pub binding: Option<QName>,
+    /// `location` of the `soap:address`/`soap12:address`
+    pub address: Option<String>,
+    /// SOAP version of the address element, `None` for non SOAP ports
+    pub soap_version: Option<SoapVersion>,
+    pub policies: Vec<PolicyAttachment>,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Service {
+    pub name: String,
+    pub ports: Vec<Port>,
+    /// policies attached to the service, they apply to all of its ports
+    pub policies: Vec<PolicyAttachment>,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsdlDocument {
+    pub version: WsdlVersion,
+    pub target_namespace: String,
+    pub schemas: SchemaSet,
+    pub messages: Vec<Message>,
+    pub port_types: Vec<PortType>,
+    pub bindings: Vec<Binding>,
+    pub services: Vec<Service>,
+    /// every `wsp:Policy` of the document that can be referenced (it has an id)
+    pub policies: Vec<Policy>,
+    /// `<!DOCTYPE>` of the document, found in the text as the XML parser skips it
+    pub doctype: Option<Doctype>,
+    /// imports and includes, filled by [`imports::ImportResolver`]
+    pub imports: Vec<Import>,
+    /// the whole document, for checks that look past the model
+    pub root: XmlElement,
+}
+
+impl WsdlDocument {
+    pub fn parse(text: &str) -> Result<Self, ParserError> {
+        let root = parse_document(text)?;
+        let mut document = if root.is(WSDL_11_NS, "definitions") {
+            Self::parse_wsdl_11(root)
+        } else if root.is(WSDL_20_NS, "description") {
+            Self::parse_wsdl_20(root)
+        } else {
+            return Err(ParserError::InvalidSOAPSpec(format!(
+                "the root element `{}` is neither a WSDL 1.1 `definitions` nor a WSDL 2.0 `description`",
+                root.name
+            )));
+        };
+        document.doctype = find_doctype(text);
+        Ok(document)
+    }
+
+    fn parse_wsdl_11(root: XmlElement) -> Self {
+        let target_namespace = root.attr("targetNamespace").unwrap_or_default().to_string();
+        let messages = root
+            .children_named(WSDL_11_NS, "message")
+            .map(|message| parse_message(message, &target_namespace))
+            .collect();
+        let port_types = root
+            .children_named(WSDL_11_NS, "portType")
+            .map(parse_port_type)
+            .collect();
+        let bindings = root
+            .children_named(WSDL_11_NS, "binding")
+            .map(|binding| parse_binding(binding, &target_namespace))
+            .collect();
+        let services = root
+            .children_named(WSDL_11_NS, "service")
+            .map(parse_service)
+            .collect();
+
+        Self {
+            version: WsdlVersion::Wsdl11,
+            target_namespace,
+            schemas: parse_schemas(&root, WSDL_11_NS),
+            messages,
+            port_types,
+            bindings,
+            services,
+            policies: parse_policies(&root),
+            doctype: None,
+            imports: vec![],
+            root,
+        }
+    }
+
+    fn parse_wsdl_20(root: XmlElement) -> Self {
+        let target_namespace = root.attr("targetNamespace").unwrap_or_default().to_string();
+        let mut messages: Vec<Message> = Vec::new();
+        let interfaces: Vec<&XmlElement> = root.children_named(WSDL_20_NS, "interface").collect();
+        let names: Vec<String> = interfaces
+            .iter()
+            .map(|interface| interface.attr("name").unwrap_or_default().to_string())
+            .collect();
+        let extends: Vec<Vec<String>> = interfaces
+            .iter()
+            .map(|interface| {
+                interface
+                    .attr("extends")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(|name| interface.resolve_qname(name).name)
+                    .collect()
+            })
+            .collect();
+        // interface faults are named in the target namespace
+        let declared_faults: Vec<Vec<(QName, QName, u64)>> = interfaces
+            .iter()
+            .map(|interface| {
+                interface
+                    .children_named(WSDL_20_NS, "fault")
+                    .filter_map(|fault| {
+                        let name = QName::new(target_namespace.clone(), fault.attr("name")?);
+                        Some((name, element_of(fault)?, fault.line))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut port_types: Vec<PortType> = Vec::new();
+        for (index, interface) in interfaces.iter().enumerate() {
+            // an operation references the faults of its interface, or of one it extends
+            let mut faults = declared_faults[index].clone();
+            for base in extended_interfaces(index, &names, &extends) {
+                faults.extend(declared_faults[base].iter().cloned());
+            }
+            port_types.push(parse_interface(
+                interface,
+                &faults,
+                &target_namespace,
+                &mut messages,
+            ));
+        }
+        inherit_operations(&mut port_types, &extends);
+        let bindings: Vec<Binding> = root
+            .children_named(WSDL_20_NS, "binding")
+            .map(|binding| parse_binding_20(binding, &target_namespace))
+            .collect();
+        let services = root
+            .children_named(WSDL_20_NS, "service")
+            .map(|service| parse_service_20(service, &bindings))
+            .collect();
+
+        Self {
+            version: WsdlVersion::Wsdl20,
+            schemas: parse_schemas(&root, WSDL_20_NS),
+            target_namespace,
+            messages,
+            port_types,
+            bindings,
+            services,
+            policies: parse_policies(&root),
+            doctype: None,
+            imports: vec![],
+            root,
+        }
+    }
+
+    /// The message an operation's `message="tns:..."` points at
+    pub fn message(&self, name: &QName) -> Option<&Message> {
+        self.messages
+            .iter()
+            .find(|m| m.name == name.name && m.namespace == name.namespace)
+    }
+
+    /// The binding a `binding="tns:..."` reference of a port points at
+    pub fn binding(&self, name: &QName) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|b| b.name == name.name && b.namespace == name.namespace)
+    }
+
+    /// Policy alternatives in effect for `operation` of `binding` at the port of a service
+    /// exposing it: those of the service, port, binding, port type and operation together.
+    /// <br>Without a port, the alternatives at every port exposing the binding, callers can use
+    /// whichever they like.
+    pub fn operation_policy(
+        &self,
+        binding: &Binding,
+        operation: &BindingOperation,
+        endpoint: Option<(&Service, &Port)>,
+    ) -> Vec<PolicyAssertions> {
+        let mut attachments = binding.policies.clone();
+        attachments.extend(operation.policies.iter().cloned());
+        let port_type = binding
+            .port_type
+            .as_ref()
+            .and_then(|name| self.port_types.iter().find(|p| p.name == name.name));
+        if let Some(port_type) = port_type {
+            attachments.extend(port_type.policies.iter().cloned());
+        }
+
+        let name = QName::new(binding.namespace.clone(), binding.name.clone());
+        let endpoints: Vec<(&Service, &Port)> = match endpoint {
+            Some(endpoint) => vec![endpoint],
+            None => self
+                .services
+                .iter()
+                .flat_map(|service| service.ports.iter().map(move |port| (service, port)))
+                .filter(|(_, port)| port.binding.as_ref() == Some(&name))
+                .collect(),
+        };
+        if endpoints.is_empty() {
+            return self.policy_alternatives(&attachments);
+        }
+        let mut alternatives: Vec<PolicyAssertions> = Vec::new();
+        for (service, port) in endpoints {
+            let mut attachments = attachments.clone();
+            attachments.extend(service.policies.iter().cloned());
+            attachments.extend(port.policies.iter().cloned());
+            for alternative in self.policy_alternatives(&attachments) {
+                if !alternatives.contains(&alternative) {
+                    alternatives.push(alternative);
+                }
+            }
+        }
+        alternatives.sort();
+        alternatives
+    }
+
+    /// Alternatives of the policies in `attachments` applying together, following policy
+    /// references
+    pub fn policy_alternatives(&self, attachments: &[PolicyAttachment]) -> Vec<PolicyAssertions> {
+        let mut alternatives = vec![PolicyAssertions::default()];
+        let mut pending: Vec<String> = Vec::new();
+        for attachment in attachments {
+            match attachment {
+                PolicyAttachment::Inline(policy) => {
+                    alternatives = combine(&alternatives, &policy.alternatives);
+                    pending.extend(policy.references.iter().cloned());
+                }
+                PolicyAttachment::Reference(id) => pending.push(id.clone()),
+            }
+        }
+        let mut visited: Vec<String> = Vec::new();
+        while let Some(id) = pending.pop() {
+            if visited.contains(&id) {
+                continue;
+            }
+            if let Some(policy) = self.policies.iter().find(|p| p.id.as_ref() == Some(&id)) {
+                alternatives = combine(&alternatives, &policy.alternatives);
+                pending.extend(policy.references.iter().cloned());
+            }
+            visited.push(id);
+        }
+        alternatives
+    }
+}
+
+/// Whether `text` is a WSDL 1.1 or 2.0 document, judging by its root element
+pub fn is_wsdl_document(text: &str) -> bool {
+    root_name(text)
+        .map(|root| {
+            (root.namespace == WSDL_11_NS && root.name == "definitions")
+                || (root.namespace == WSDL_20_NS && root.name == "description")
+        })
+        .unwrap_or(false)
+}
+
+fn parse_schemas(root: &XmlElement, wsdl_namespace: &str) -> SchemaSet {
+    let schemas = root
+        .children_named(wsdl_namespace, "types")
+        .flat_map(|types| types.children_named(XSD_NS, "schema"))
+        .map(Schema::parse)
+        .collect();
+    SchemaSet { schemas }
+}
+
+/// Every `wsp:Policy` of the document that has an id
+fn parse_policies(root: &XmlElement) -> Vec<Policy> {
+    root.descendants()
+        .into_iter()
+        .filter(|element| is_policy_element(element))
+        .map(parse_policy)
+        .filter(|policy| policy.id.is_some())
+        .collect()
+}
+
+fn parse_message(message: &XmlElement, target_namespace: &str) -> Message {
+    Message {
+        name: message.attr("name").unwrap_or_default().to_string(),
+        namespace: target_namespace.to_string(),
+        parts: message
+            .children_named(WSDL_11_NS, "part")
+            .map(|part| MessagePart {
+                name: part.attr("name").unwrap_or_default().to_string(),
+                element: part.qname_attr("element"),
+                type_name: part.qname_attr("type"),
+            })
+            .collect(),
+        line: message.line,
+    }
+}
+
+fn parse_port_type(port_type: &XmlElement) -> PortType {
+    let operations = port_type
+        .children_named(WSDL_11_NS, "operation")
+        .map(|operation| {
+            let message_of = |direction: &str| {
+                operation
+                    .child(WSDL_11_NS, direction)
+                    .and_then(|m| m.qname_attr("message"))
+            };
+            PortTypeOperation {
+                name: operation.attr("name").unwrap_or_default().to_string(),
+                input: message_of("input"),
+                output: message_of("output"),
+                faults: operation
+                    .children_named(WSDL_11_NS, "fault")
+                    .filter_map(|f| f.qname_attr("message"))
+                    .collect(),
+                line: operation.line,
+            }
+        })
+        .collect();
+    PortType {
+        name: port_type.attr("name").unwrap_or_default().to_string(),
+        operations,
+        policies: attached_policies(port_type),
+        line: port_type.line,
+    }
+}
+
+fn parse_binding(binding: &XmlElement, target_namespace: &str) -> Binding {
+    let soap_binding = binding
+        .child(SOAP_11_BINDING_NS, "binding")
+        .map(|b| (SoapVersion::Soap11, b))
+        .or_else(|| {
+            binding
+                .child(SOAP_12_BINDING_NS, "binding")
+                .map(|b| (SoapVersion::Soap12, b))
+        });
+    let soap_version = soap_binding.map(|(version, _)| version);
+
+    let operations = binding
+        .children_named(WSDL_11_NS, "operation")
+        .map(|operation| {
+            let soap_operation = operation
+                .child(SOAP_11_BINDING_NS, "operation")
+                .or_else(|| operation.child(SOAP_12_BINDING_NS, "operation"));
+            let input = operation.child(WSDL_11_NS, "input");
+            let soap_child = |name: &'static str| {
+                input.into_iter().flat_map(move |input| {
+                    input.children.iter().filter(move |c| {
+                        c.is(SOAP_11_BINDING_NS, name) || c.is(SOAP_12_BINDING_NS, name)
+                    })
+                })
+            };
+            let body = soap_child("body").next();
+            let mut policies = attached_policies(operation);
+            for message in &operation.children {
+                if message.namespace == WSDL_11_NS
+                    && ["input", "output", "fault"].contains(&message.name.as_str())
+                {
+                    policies.extend(attached_policies(message));
+                }
+            }
+            BindingOperation {
+                name: operation.attr("name").unwrap_or_default().to_string(),
+                soap_action: soap_operation
+                    .and_then(|o| o.attr("soapAction"))
+                    .map(String::from),
+                style: soap_operation
+                    .and_then(|o| o.attr("style"))
+                    .map(String::from),
+                input_namespace: body
+                    .and_then(|body| body.attr("namespace"))
+                    .map(String::from),
+                input_body_parts: body
+                    .and_then(|body| body.attr("parts"))
+                    .map(|parts| parts.split_whitespace().map(String::from).collect()),
+                input_headers: soap_child("header")
+                    .filter_map(|header| {
+                        Some(SoapHeader {
+                            message: header.qname_attr("message")?,
+                            part: header.attr("part")?.to_string(),
+                        })
+                    })
+                    .collect(),
+                policies,
+                line: operation.line,
+            }
+        })
+        .collect();
+
+    Binding {
+        name: binding.attr("name").unwrap_or_default().to_string(),
+        namespace: target_namespace.to_string(),
+        port_type: binding.qname_attr("type"),
+        soap_version,
+        transport: soap_binding
+            .and_then(|(_, b)| b.attr("transport"))
+            .map(String::from),
+        style: soap_binding
+            .and_then(|(_, b)| b.attr("style"))
+            .map(String::from),
+        policies: attached_policies(binding),
+        operations,
+        line: binding.line,
+    }
+}
+
+fn parse_service(service: &XmlElement) -> Service {
+    let ports = service
+        .children_named(WSDL_11_NS, "port")
+        .map(|port| {
+            let address = port
+                .child(SOAP_11_BINDING_NS, "address")
+                .map(|a| (SoapVersion::Soap11, a))
+                .or_else(|| {
+                    port.child(SOAP_12_BINDING_NS, "address")
+                        .map(|a| (SoapVersion::Soap12, a))
+                });
+            Port {
+                name: port.attr("name").unwrap_or_default().to_string(),
+                binding: port.qname_attr("binding"),
+                address: address
+                    .and_then(|(_, a)| a.attr("location"))
+                    .map(String::from),
+                soap_version: address.map(|(version, _)| version),
+                policies: attached_policies(port),
+                line: port.line,
+            }
+        })
+        .collect();
+    Service {
+        name: service.attr("name").unwrap_or_default().to_string(),
+        ports,
+        policies: attached_policies(service),
+        line: service.line,
+    }
+}
+
+/// A WSDL 2.0 `interface` as a port type. The element of each operation's input, output and
+/// faults is wrapped in a single part message added to `messages`. `faults` holds the name,
+/// element and line of the faults the interface can reference, its own first.
+fn parse_interface(
+    interface: &XmlElement,
+    faults: &[(QName, QName, u64)],
+    target_namespace: &str,
+    messages: &mut Vec<Message>,
+) -> PortType {
+    let mut operations = Vec::new();
+    for operation in interface.children_named(WSDL_20_NS, "operation") {
+        let mut message_of = |direction: &str| {
+            let message = operation.child(WSDL_20_NS, direction)?;
+            let element = element_of(message)?;
+            Some(wrap_element(
+                element,
+                message.line,
+                target_namespace,
+                messages,
+            ))
+        };
+        let input = message_of("input");
+        let output = message_of("output");
+        let fault_refs: Vec<QName> = operation
+            .children
+            .iter()
+            .filter(|c| c.is(WSDL_20_NS, "infault") || c.is(WSDL_20_NS, "outfault"))
+            .filter_map(|c| c.qname_attr("ref"))
+            .collect();
+        let faults = fault_refs
+            .iter()
+            .filter_map(|reference| faults.iter().find(|(name, ..)| name == reference))
+            .map(|(_, element, line)| {
+                wrap_element(element.clone(), *line, target_namespace, messages)
+            })
+            .collect();
+        operations.push(PortTypeOperation {
+            name: operation.attr("name").unwrap_or_default().to_string(),
+            input,
+            output,
+            faults,
+            line: operation.line,
+        });
+    }
+
+    PortType {
+        name: interface.attr("name").unwrap_or_default().to_string(),
+        operations,
+        policies: attached_policies(interface),
+        line: interface.line,
+    }
+}
+
+/// The single part message carrying `element`, created on first use. It is named after the
+/// element, suffixed when another element already took the name.
+fn wrap_element(
+    element: QName,
+    line: u64,
+    target_namespace: &str,
+    messages: &mut Vec<Message>,
+) -> QName {
+    let existing = messages
+        .iter()
+        .find(|m| m.parts.iter().any(|p| p.element.as_ref() == Some(&element)));
+    if let Some(message) = existing {
+        return QName::new(target_namespace, message.name.clone());
+    }
+    let mut name = element.name.clone();
+    let mut suffix = 1;
+    while messages.iter().any(|m| m.name == name) {
+        suffix += 1;
+        name = format!("{}{}", element.name, suffix);
+    }
+    messages.push(Message {
+        name: name.clone(),
+        namespace: target_namespace.to_string(),
+        parts: vec![MessagePart {
+            name: "body".to_string(),
+            element: Some(element),
+            type_name: None,
+        }],
+        line,
+    });
+    QName::new(target_namespace, name)
+}
+
+/// Element of a WSDL 2.0 input, output or fault. `#any`, `#none` and `#other` stand for no
+/// particular element
+fn element_of(message: &XmlElement) -> Option<QName> {
+    message
+        .attr("element")
+        .filter(|element| !element.starts_with('#'))
+        .map(|element| message.resolve_qname(element))
+}
+
+/// Add the operations of the interfaces each port type `extends` (by index) to it
+fn inherit_operations(port_types: &mut [PortType], extends: &[Vec<String>]) {
+    let names: Vec<String> = port_types.iter().map(|p| p.name.clone()).collect();
+    for index in 0..port_types.len() {
+        let inherited: Vec<PortTypeOperation> = extended_interfaces(index, &names, extends)
+            .into_iter()
+            .flat_map(|base| port_types[base].operations.clone())
+            .collect();
+        for operation in inherited {
+            let operations = &mut port_types[index].operations;
+            if !operations.iter().any(|o| o.name == operation.name) {
+                operations.push(operation);
+            }
+        }
+    }
+}
+
+/// Indexes of the interfaces the interface at `index` extends, directly or not, depth first.
+/// `names` and `extends` give the name and extended interface names of every interface.
+fn extended_interfaces(index: usize, names: &[String], extends: &[Vec<String>]) -> Vec<usize> {
+    let mut bases = Vec::new();
+    let mut pending: Vec<&String> = extends[index].iter().rev().collect();
+    let mut visited = vec![&names[index]];
+    while let Some(name) = pending.pop() {
+        if visited.contains(&name) {
+            continue;
+        }
+        if let Some(base) = names.iter().position(|n| n == name) {
+            bases.push(base);
+            pending.extend(extends[base].iter().rev());
+        }
+        visited.push(name);
+    }
+    bases
+}
+
+fn parse_binding_20(binding: &XmlElement, target_namespace: &str) -> Binding {
+    let is_soap = binding.attr("type") == Some(WSDL_20_SOAP_NS);
+    // `wsoap:version` defaults to 1.2
+    let soap_version = is_soap.then(|| match binding.attr_ns(WSDL_20_SOAP_NS, "version") {
+        Some("1.1") => SoapVersion::Soap11,
+        _ => SoapVersion::Soap12,
+    });
+
+    let operations = binding
+        .children_named(WSDL_20_NS, "operation")
+        .map(|operation| {
+            let mut policies = attached_policies(operation);
+            for message in &operation.children {
+                if message.namespace == WSDL_20_NS
+                    && ["input", "output", "infault", "outfault"].contains(&message.name.as_str())
+                {
+                    policies.extend(attached_policies(message));
+                }
+            }
+            BindingOperation {
+                name: operation
+                    .qname_attr("ref")
+                    .map(|reference| reference.name)
+                    .unwrap_or_default(),
+                soap_action: operation
+                    .attr_ns(WSDL_20_SOAP_NS, "action")
+                    .map(String::from),
+                style: None,
+                input_namespace: None,
+                input_body_parts: None,
+                input_headers: vec![],
+                policies,
+                line: operation.line,
+            }
+        })
+        .collect();
+
+    Binding {
+        name: binding.attr("name").unwrap_or_default().to_string(),
+        namespace: target_namespace.to_string(),
+        port_type: binding.qname_attr("interface"),
+        soap_version,
+        transport: binding
+            .attr_ns(WSDL_20_SOAP_NS, "protocol")
+            .map(String::from),
+        // WSDL 2.0 SOAP bindings are always document style
+        style: is_soap.then(|| "document".to_string()),
+        policies: attached_policies(binding),
+        operations,
+        line: binding.line,
+    }
+}
+
+fn parse_service_20(service: &XmlElement, bindings: &[Binding]) -> Service {
+    let ports = service
+        .children_named(WSDL_20_NS, "endpoint")
+        .map(|endpoint| {
+            let binding = endpoint.qname_attr("binding");
+            let soap_version = binding.as_ref().and_then(|name| {
+                bindings
+                    .iter()
+                    .find(|b| b.name == name.name)
+                    .and_then(|b| b.soap_version)
+            });
+            Port {
+                name: endpoint.attr("name").unwrap_or_default().to_string(),
+                binding,
+                address: endpoint.attr("address").map(String::from),
+                soap_version,
+                policies: attached_policies(endpoint),
+                line: endpoint.line,
+            }
+        })
+        .collect();
+    Service {
+        name: service.attr("name").unwrap_or_default().to_string(),
+        ports,
+        policies: attached_policies(service),
+        line: service.line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wsdl_20_fault_references_resolve_within_the_interface_and_what_it_extends() {
+        let wsdl = WsdlDocument::parse(
+            r#"<description xmlns="http://www.w3.org/ns/wsdl" xmlns:tns="urn:t" xmlns:other="urn:other" xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:t">
+  <types>
+    <xs:schema targetNamespace="urn:t">
+      <xs:element name="Request" type="xs:string"/>
+      <xs:element name="AccountProblem" type="xs:string"/>
+      <xs:element name="OrderProblem" type="xs:string"/>
+    </xs:schema>
+  </types>
+  <interface name="Accounts">
+    <fault name="InvalidInput" element="tns:AccountProblem"/>
+    <operation name="Open" pattern="http://www.w3.org/ns/wsdl/in-out">
+      <input element="tns:Request"/><outfault ref="tns:InvalidInput"/>
+    </operation>
+  </interface>
+  <interface name="Orders">
+    <fault name="InvalidInput" element="tns:OrderProblem"/>
+    <operation name="Place" pattern="http://www.w3.org/ns/wsdl/in-out">
+      <input element="tns:Request"/><outfault ref="tns:InvalidInput"/>
+    </operation>
+  </interface>
+  <interface name="PriorityOrders" extends="tns:Orders">
+    <operation name="Expedite" pattern="http://www.w3.org/ns/wsdl/in-out">
+      <input element="tns:Request"/><outfault ref="tns:InvalidInput"/><outfault ref="other:InvalidInput"/>
+    </operation>
+  </interface>
+</description>"#,
+        )
+        .unwrap();
+        let fault_elements = |interface: &str, operation: &str| -> Vec<String> {
+            let port_type = wsdl
+                .port_types
+                .iter()
+                .find(|p| p.name == interface)
+                .unwrap();
+            let operation = port_type
+                .operations
+                .iter()
+                .find(|o| o.name == operation)
+                .unwrap();
+            operation
+                .faults
+                .iter()
+                .map(|fault| {
+                    let message = wsdl.message(fault).unwrap();
+                    message.parts[0].element.as_ref().unwrap().name.clone()
+                })
+                .collect()
+        };
+
+        assert_eq!(fault_elements("Accounts", "Open"), ["AccountProblem"]);
+        assert_eq!(fault_elements("Orders", "Place"), ["OrderProblem"]);
+        // inherited from Orders, `other:InvalidInput` is in another namespace
+        assert_eq!(
+            fault_elements("PriorityOrders", "Expedite"),
+            ["OrderProblem"]
+        );
+        assert_eq!(fault_elements("PriorityOrders", "Place"), ["OrderProblem"]);
+    }
+}
@@ -0,0 +1,621 @@
+use serde_json::{json, Map, Value};
+
+use crate::wsdl::policy::is_secured;
+use crate::wsdl::schema::{ComplexType, ElementDecl, Facets, SimpleType};
+use crate::wsdl::xml::QName;
+use crate::wsdl::{Message, MessagePart, SoapHeader, SoapVersion, WsdlDocument, XSD_NS};
+
+/// Name of the security scheme standing for the WS-Security header
+const WS_SECURITY_SCHEME: &str = "wsSecurity";
+/// `info.version` of the converted document, WSDL doesn't version services
+const PLACEHOLDER_VERSION: &str = "1.0.0";
+
+/// Map a WSDL into an OpenAPI 3 document so the REST rules can run over SOAP services.
+///
+/// Every operation becomes a `POST /{port type}/{operation}` whose request body is the input
+/// message, `200` response the output message and `500` response its faults (SOAP faults travel
+/// with that status). Global elements and named types become component schemas. Operations a
+/// WS-SecurityPolicy secures in every binding require the `wsSecurity` scheme, the others
+/// nothing.
+pub fn to_openapi(wsdl: &WsdlDocument) -> Value {
+    let names = ComponentNames::of(wsdl);
+    let converter = Converter {
+        wsdl,
+        names: &names,
+    };
+
+    let mut paths = Map::new();
+    let mut any_secured = false;
+    for port_type in &wsdl.port_types {
+        for operation in &port_type.operations {
+            let bound: Vec<_> = wsdl
+                .bindings
+                .iter()
+                .filter(|b| b.port_type.as_ref().map(|p| &p.name) == Some(&port_type.name))
+                .filter_map(|b| {
+                    b.operations
+                        .iter()
+                        .find(|o| o.name == operation.name)
+                        .map(|o| (b, o))
+                })
+                .collect();
+
+            let mut media_types: Vec<&str> = bound
+                .iter()
+                .filter_map(|(binding, _)| binding.soap_version)
+                .map(|version| match version {
+                    SoapVersion::Soap11 => "text/xml",
+                    SoapVersion::Soap12 => "application/soap+xml",
+                })
+                .collect();
+            media_types.sort_unstable();
+            media_types.dedup();
+            if media_types.is_empty() {
+                media_types.push("text/xml");
+            }
+            let content = |schema: Value| -> Value {
+                let mut content = Map::new();
+                for media_type in &media_types {
+                    content.insert(media_type.to_string(), json!({ "schema": schema }));
+                }
+                Value::Object(content)
+            };
+
+            let mut post = Map::new();
+            post.insert("operationId".into(), json!(operation.name));
+            post.insert("tags".into(), json!([port_type.name]));
+            let input = operation
+                .input
+                .as_ref()
+                .and_then(|name| Some((name, wsdl.message(name)?)));
+            if let Some((input_name, input)) = input {
+                // parts bound to the SOAP header, or left out of the body, aren't in the body
+                let mut excluded: Vec<&str> = Vec::new();
+                let mut parameters: Vec<Value> = Vec::new();
+                if let Some((_, bound)) = bound.first() {
+                    if let Some(body_parts) = &bound.input_body_parts {
+                        excluded.extend(
+                            input
+                                .parts
+                                .iter()
+                                .map(|part| part.name.as_str())
+                                .filter(|part| !body_parts.iter().any(|p| p == part)),
+                        );
+                    }
+                    for header in &bound.input_headers {
+                        if &header.message == input_name {
+                            excluded.push(&header.part);
+                        }
+                        if let Some(parameter) = converter.header(header) {
+                            parameters.push(parameter);
+                        }
+                    }
+                }
+                if !parameters.is_empty() {
+                    post.insert("parameters".into(), json!(parameters));
+                }
+                if input
+                    .parts
+                    .iter()
+                    .any(|part| !excluded.contains(&part.name.as_str()))
+                {
+                    let body = converter.message(input, &excluded);
+                    post.insert(
+                        "requestBody".into(),
+                        json!({ "required": true, "content": content(body) }),
+                    );
+                }
+            }
+            let mut responses = Map::new();
+            let output = operation.output.as_ref().and_then(|m| wsdl.message(m));
+            responses.insert(
+                "200".into(),
+                match output {
+                    Some(output) => json!({
+                        "description": format!("`{}` message", output.name),
+                        "content": content(converter.message(output, &[])),
+                    }),
+                    None => json!({ "description": "One way operation, no response" }),
+                },
+            );
+            let faults: Vec<Value> = operation
+                .faults
+                .iter()
+                .filter_map(|m| wsdl.message(m))
+                .map(|fault| converter.message(fault, &[]))
+                .collect();
+            if !faults.is_empty() {
+                let schema = match faults.len() {
+                    1 => faults[0].clone(),
+                    _ => json!({ "oneOf": faults }),
+                };
+                responses.insert(
+                    "500".into(),
+                    json!({ "description": "SOAP fault", "content": content(schema) }),
+                );
+            }
+            post.insert("responses".into(), Value::Object(responses));
+
+            if let Some((binding, bound)) = bound.first() {
+                if let Some(action) = &bound.soap_action {
+                    post.insert("x-soap-action".into(), json!(action));
+                }
+                let style = bound.style.as_ref().or(binding.style.as_ref());
+                post.insert(
+                    "x-soap-style".into(),
+                    json!(style.map_or("document", |s| s)),
+                );
+            }
+            // the weakest binding decides, callers can pick any of them
+            let secured = !bound.is_empty()
+                && bound.iter().all(|(binding, bound)| {
+                    is_secured(&wsdl.operation_policy(binding, bound, None))
+                });
+            if secured {
+                any_secured = true;
+                post.insert("security".into(), json!([{ WS_SECURITY_SCHEME: [] }]));
+            }
+
+            paths.insert(
+                format!("/{}/{}", port_type.name, operation.name),
+                json!({ "post": post }),
+            );
+        }
+    }
+
+    let mut servers: Vec<Value> = Vec::new();
+    for service in &wsdl.services {
+        for port in &service.ports {
+            let Some(address) = &port.address else {
+                continue;
+            };
+            if !servers.iter().any(|s| s["url"] == json!(address)) {
+                servers.push(json!({
+                    "url": address,
+                    "description": format!("port `{}` of service `{}`", port.name, service.name),
+                }));
+            }
+        }
+    }
+
+    let title = wsdl
+        .services
+        .first()
+        .map(|service| service.name.clone())
+        .unwrap_or_else(|| wsdl.target_namespace.clone());
+    let mut security_schemes = Map::new();
+    if any_secured {
+        security_schemes.insert(
+            WS_SECURITY_SCHEME.into(),
+            json!({
+                "type": "apiKey",
+                "in": "header",
+                "name": "Security",
+                "description": "WS-Security SOAP header required by the operation's WS-SecurityPolicy",
+            }),
+        );
+    }
+    json!({
+        "openapi": "3.0.3",
+        // WSDL has no notion of version or contact
+        "info": { "title": title, "version": PLACEHOLDER_VERSION },
+        "servers": servers,
+        "paths": paths,
+        "components": {
+            "schemas": converter.component_schemas(),
+            "securitySchemes": security_schemes,
+        },
+        "x-wsdl-target-namespace": wsdl.target_namespace,
+    })
+}
+
+/// Component schema name of every global element and named type. Elements and types live in
+/// separate symbol spaces in XSD, so an element sharing its name with a type is suffixed.
+struct ComponentNames {
+    types: Vec<(QName, String)>,
+    elements: Vec<(QName, String)>,
+}
+
+impl ComponentNames {
+    fn of(wsdl: &WsdlDocument) -> Self {
+        let mut names = Self {
+            types: vec![],
+            elements: vec![],
+        };
+        let mut taken: Vec<String> = Vec::new();
+        let mut unique = |name: &str, suffix: &str| -> String {
+            let mut candidate = name.to_string();
+            if taken.contains(&candidate) {
+                candidate = format!("{}{}", name, suffix);
+            }
+            let mut counter = 1;
+            while taken.contains(&candidate) {
+                counter += 1;
+                candidate = format!("{}{}{}", name, suffix, counter);
+            }
+            taken.push(candidate.clone());
+            candidate
+        };
+        for schema in &wsdl.schemas.schemas {
+            let type_names = schema
+                .complex_types
+                .iter()
+                .filter_map(|t| t.name.as_deref())
+                .chain(schema.simple_types.iter().filter_map(|t| t.name.as_deref()));
+            for name in type_names {
+                let qname = QName::new(schema.target_namespace.clone(), name);
+                names.types.push((qname, unique(name, "Type")));
+            }
+        }
+        for schema in &wsdl.schemas.schemas {
+            for element in &schema.elements {
+                let qname = QName::new(schema.target_namespace.clone(), element.name.clone());
+                names
+                    .elements
+                    .push((qname, unique(&element.name, "Element")));
+            }
+        }
+        names
+    }
+    fn type_name(&self, name: &QName) -> Option<&str> {
+        self.types
+            .iter()
+            .find(|(qname, _)| qname == name)
+            .map(|(_, component)| component.as_str())
+    }
+    fn element_name(&self, name: &QName) -> Option<&str> {
+        self.elements
+            .iter()
+            .find(|(qname, _)| qname == name)
+            .map(|(_, component)| component.as_str())
+    }
+}
+
+struct Converter<'c> {
+    wsdl: &'c WsdlDocument,
+    names: &'c ComponentNames,
+}
+
+impl Converter<'_> {
+    fn component_schemas(&self) -> Map<String, Value> {
+        let mut schemas = Map::new();
+        for schema in &self.wsdl.schemas.schemas {
+            let namespace = &schema.target_namespace;
+            for complex_type in &schema.complex_types {
+                let Some(name) = &complex_type.name else {
+                    continue;
+                };
+                let qname = QName::new(namespace.clone(), name.clone());
+                if let Some(component) = self.names.type_name(&qname) {
+                    schemas.insert(component.to_string(), self.complex_type(complex_type));
+                }
+            }
+            for simple_type in &schema.simple_types {
+                let Some(name) = &simple_type.name else {
+                    continue;
+                };
+                let qname = QName::new(namespace.clone(), name.clone());
+                if let Some(component) = self.names.type_name(&qname) {
+                    schemas.insert(component.to_string(), self.simple_type(simple_type));
+                }
+            }
+            for element in &schema.elements {
+                let qname = QName::new(namespace.clone(), element.name.clone());
+                if let Some(component) = self.names.element_name(&qname) {
+                    let mut converted = self.element_type(element);
+                    // `$ref` siblings are ignored by OpenAPI 3.0
+                    if converted.get("$ref").is_some() {
+                        converted = json!({ "allOf": [converted] });
+                    }
+                    if let Some(object) = converted.as_object_mut() {
+                        object.insert(
+                            "xml".into(),
+                            json!({ "name": element.name, "namespace": namespace }),
+                        );
+                    }
+                    schemas.insert(component.to_string(), converted);
+                }
+            }
+        }
+        schemas
+    }
+
+    /// Body of a message but its `excluded` parts: the element of a single part document style
+    /// body, an object of the parts otherwise. Every part of a message has to be sent.
+    fn message(&self, message: &Message, excluded: &[&str]) -> Value {
+        let parts: Vec<&MessagePart> = message
+            .parts
+            .iter()
+            .filter(|part| !excluded.contains(&part.name.as_str()))
+            .collect();
+        if let [part] = parts.as_slice() {
+            if let Some(element) = &part.element {
+                return self.element_reference(element);
+            }
+        }
+        let mut properties = Map::new();
+        for part in &parts {
+            properties.insert(part.name.clone(), self.part(part));
+        }
+        let required: Vec<&str> = parts.iter().map(|p| p.name.as_str()).collect();
+        json!({ "type": "object", "properties": properties, "required": required })
+    }
+
+    fn part(&self, part: &MessagePart) -> Value {
+        match (&part.element, &part.type_name) {
+            (Some(element), _) => self.element_reference(element),
+            (None, Some(type_name)) => self.type_reference(type_name),
+            (None, None) => json!({}),
+        }
+    }
+
+    /// A SOAP header as a header parameter named after its element, so credentials passed in
+    /// it (e.g. `Security`, `AuthHeader`) show up like any header borne credential
+    fn header(&self, header: &SoapHeader) -> Option<Value> {
+        let message = self.wsdl.message(&header.message)?;
+        let part = message.parts.iter().find(|part| part.name == header.part)?;
+        let name = part
+            .element
+            .as_ref()
+            .map_or(&part.name, |element| &element.name);
+        Some(json!({
+            "name": name,
+            "in": "header",
+            "required": true,
+            "description": format!("SOAP header, part `{}` of message `{}`", part.name, message.name),
+            "schema": self.part(part),
+        }))
+    }
+
+    fn element_reference(&self, element: &QName) -> Value {
+        match self.names.element_name(element) {
+            Some(component) => json!({ "$ref": format!("#/components/schemas/{}", component) }),
+            None => json!({}),
+        }
+    }
+
+    fn type_reference(&self, type_name: &QName) -> Value {
+        if type_name.namespace == XSD_NS {
+            return builtin(&type_name.name);
+        }
+        match self.names.type_name(type_name) {
+            Some(component) => json!({ "$ref": format!("#/components/schemas/{}", component) }),
+            None => json!({}),
+        }
+    }
+
+    /// Schema of an element's value, whatever its occurrences
+    fn element_type(&self, element: &ElementDecl) -> Value {
+        let mut schema = if let Some(reference) = &element.reference {
+            self.element_reference(reference)
+        } else if let Some(complex_type) = &element.complex_type {
+            self.complex_type(complex_type)
+        } else if let Some(simple_type) = &element.simple_type {
+            self.simple_type(simple_type)
+        } else if let Some(type_name) = &element.type_name {
+            self.type_reference(type_name)
+        } else {
+            json!({})
+        };
+        // `$ref` siblings are ignored by OpenAPI 3.0
+        if let Some(object) = schema.as_object_mut().filter(|o| !o.contains_key("$ref")) {
+            if let Some(default) = &element.default {
+                object.insert("default".into(), json!(default));
+            }
+            if element.nillable {
+                object.insert("nullable".into(), json!(true));
+            }
+        }
+        schema
+    }
+
+    fn complex_type(&self, complex_type: &ComplexType) -> Value {
+        let mut properties = Map::new();
+        let mut required: Vec<String> = Vec::new();
+        for element in &complex_type.elements {
+            let value = self.element_type(element);
+            let schema = if element.max_occurs == Some(1) {
+                value
+            } else {
+                let mut array = json!({ "type": "array", "items": value });
+                if let Some(max) = element.max_occurs {
+                    array["maxItems"] = json!(max);
+                }
+                if element.min_occurs > 1 {
+                    array["minItems"] = json!(element.min_occurs);
+                }
+                array
+            };
+            if element.min_occurs > 0 {
+                required.push(element.name.clone());
+            }
+            properties.insert(element.name.clone(), schema);
+        }
+        for attribute in &complex_type.attributes {
+            let mut schema = match (&attribute.simple_type, &attribute.type_name) {
+                (Some(simple_type), _) => self.simple_type(simple_type),
+                (None, Some(type_name)) => self.type_reference(type_name),
+                (None, None) => builtin("string"),
+            };
+            if let Some(object) = schema.as_object_mut().filter(|o| !o.contains_key("$ref")) {
+                object.insert("xml".into(), json!({ "attribute": true }));
+                if let Some(default) = &attribute.default {
+                    object.insert("default".into(), json!(default));
+                }
+            }
+            if attribute.required {
+                required.push(attribute.name.clone());
+            }
+            properties.insert(attribute.name.clone(), schema);
+        }
+
+        let mut object = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            object["required"] = json!(required);
+        }
+        if !complex_type.any_elements.is_empty() {
+            object["additionalProperties"] = json!(true);
+        }
+        if complex_type.mixed {
+            object["x-xsd-mixed"] = json!(true);
+        }
+        match &complex_type.base {
+            // simple content: the text value of the base type, and the attributes
+            Some(base)
+                if base.namespace == XSD_NS || self.wsdl.schemas.simple_type(base).is_some() =>
+            {
+                if properties_empty(&object) {
+                    self.type_reference(base)
+                } else {
+                    object["properties"]["value"] = self.type_reference(base);
+                    object
+                }
+            }
+            Some(base) => json!({ "allOf": [self.type_reference(base), object] }),
+            None => object,
+        }
+    }
+
+    fn simple_type(&self, simple_type: &SimpleType) -> Value {
+        let (base, facets) = self.wsdl.schemas.restriction_of(simple_type);
+        let mut schema = builtin(base.as_ref().map_or("string", |b| b.name.as_str()));
+        apply_facets(&mut schema, &facets);
+        schema
+    }
+}
+
+fn properties_empty(object: &Value) -> bool {
+    object["properties"]
+        .as_object()
+        .map(Map::is_empty)
+        .unwrap_or(true)
+}
+
+/// JSON schema of a built in XSD type
+fn builtin(name: &str) -> Value {
+    match name {
+        "boolean" => json!({ "type": "boolean" }),
+        "int" | "short" | "byte" | "unsignedShort" | "unsignedByte" => {
+            json!({ "type": "integer", "format": "int32" })
+        }
+        "long" | "unsignedInt" => json!({ "type": "integer", "format": "int64" }),
+        "integer" | "nonNegativeInteger" | "positiveInteger" | "negativeInteger"
+        | "nonPositiveInteger" | "unsignedLong" => json!({ "type": "integer" }),
+        "decimal" => json!({ "type": "number" }),
+        "double" => json!({ "type": "number", "format": "double" }),
+        "float" => json!({ "type": "number", "format": "float" }),
+        "date" => json!({ "type": "string", "format": "date" }),
+        "dateTime" => json!({ "type": "string", "format": "date-time" }),
+        "base64Binary" => json!({ "type": "string", "format": "byte" }),
+        "anyURI" => json!({ "type": "string", "format": "uri" }),
+        "anyType" => json!({}),
+        _ => json!({ "type": "string" }),
+    }
+}
+
+fn apply_facets(schema: &mut Value, facets: &Facets) {
+    if let Some(length) = facets.length {
+        schema["minLength"] = json!(length);
+        schema["maxLength"] = json!(length);
+    }
+    if let Some(min_length) = facets.min_length {
+        schema["minLength"] = json!(min_length);
+    }
+    if let Some(max_length) = facets.max_length {
+        schema["maxLength"] = json!(max_length);
+    }
+    if let Some(pattern) = &facets.pattern {
+        schema["pattern"] = json!(pattern);
+    }
+    if !facets.enumeration.is_empty() {
+        schema["enum"] = json!(facets.enumeration);
+    }
+    let number =
+        |value: &Option<String>| value.as_deref().and_then(|v| v.trim().parse::<f64>().ok());
+    if let Some(minimum) = number(&facets.min_inclusive) {
+        schema["minimum"] = json!(minimum);
+    }
+    if let Some(maximum) = number(&facets.max_inclusive) {
+        schema["maximum"] = json!(maximum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS_WSDL: &str = r##"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/" xmlns:wsp="http://www.w3.org/ns/ws-policy" xmlns:wsu="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd" xmlns:sp="http://docs.oasis-open.org/ws-sx/ws-securitypolicy/200702" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:orders" targetNamespace="urn:orders">
+  <wsp:Policy wsu:Id="Secure"><sp:TransportBinding/></wsp:Policy>
+  <types>
+    <xs:schema targetNamespace="urn:orders">
+      <xs:complexType name="Order"><xs:sequence><xs:element name="id" type="xs:string"/></xs:sequence></xs:complexType>
+      <xs:element name="Order" type="tns:Order"/>
+      <xs:element name="AuthHeader" type="xs:string"/>
+    </xs:schema>
+  </types>
+  <message name="PlaceOrder"><part name="auth" element="tns:AuthHeader"/><part name="order" element="tns:Order"/></message>
+  <message name="OrderPlaced"><part name="order" element="tns:Order"/></message>
+  <portType name="Orders">
+    <operation name="Place"><input message="tns:PlaceOrder"/><output message="tns:OrderPlaced"/></operation>
+  </portType>
+  <binding name="Soap11" type="tns:Orders">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place">
+      <wsp:PolicyReference URI="#Secure"/>
+      <soap:operation soapAction="urn:place"/>
+      <input><soap:header message="tns:PlaceOrder" part="auth" use="literal"/><soap:body parts="order" use="literal"/></input>
+      <output><soap:body use="literal"/></output>
+    </operation>
+  </binding>
+  <binding name="Soap12" type="tns:Orders" wsp:PolicyURIs="#Secure">
+    <soap12:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place"><input><soap12:body use="literal"/></input><output><soap12:body use="literal"/></output></operation>
+  </binding>
+  <binding name="Soap11Again" type="tns:Orders" wsp:PolicyURIs="#Secure">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Place"><input><soap:body use="literal"/></input><output><soap:body use="literal"/></output></operation>
+  </binding>
+</definitions>"##;
+
+    #[test]
+    fn converts_operations_headers_security_and_components() {
+        let wsdl = WsdlDocument::parse(ORDERS_WSDL).unwrap();
+        let openapi = to_openapi(&wsdl);
+
+        let paths: Vec<&String> = openapi["paths"].as_object().unwrap().keys().collect();
+        assert_eq!(paths, ["/Orders/Place"]);
+        let post = &openapi["paths"]["/Orders/Place"]["post"];
+        assert_eq!(post["security"], json!([{ "wsSecurity": [] }]));
+        assert_eq!(openapi["info"]["version"], "1.0.0");
+        assert!(openapi["components"]["securitySchemes"]["wsSecurity"].is_object());
+
+        let media_types: Vec<&String> = post["requestBody"]["content"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(media_types, ["application/soap+xml", "text/xml"]);
+        assert_eq!(
+            post["requestBody"]["content"]["text/xml"]["schema"],
+            json!({ "$ref": "#/components/schemas/OrderElement" })
+        );
+        assert_eq!(post["parameters"][0]["name"], "AuthHeader");
+        assert_eq!(post["parameters"][0]["in"], "header");
+
+        let components: Vec<&String> = openapi["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(components, ["AuthHeader", "Order", "OrderElement"]);
+    }
+
+    #[test]
+    fn operations_are_secured_only_when_every_binding_is() {
+        let partly_secured = ORDERS_WSDL.replacen(r##" wsp:PolicyURIs="#Secure""##, "", 1);
+        let openapi = to_openapi(&WsdlDocument::parse(&partly_secured).unwrap());
+
+        assert!(openapi["paths"]["/Orders/Place"]["post"]["security"].is_null());
+        assert_eq!(openapi["components"]["securitySchemes"], json!({}));
+    }
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use crate::wsdl::xml::XmlElement;
+
+/// WS-Policy 1.2 and 1.5 namespaces
+pub const WS_POLICY_NS: [&str; 2] = [
+    "http://schemas.xmlsoap.org/ws/2004/09/policy",
+    "http://www.w3.org/ns/ws-policy",
+];
+/// WS-SecurityPolicy 1.1, 1.2 and 1.3 namespaces
+pub const WS_SECURITY_POLICY_NS: [&str; 3] = [
+    "http://schemas.xmlsoap.org/ws/2005/07/securitypolicy",
+    "http://docs.oasis-open.org/ws-sx/ws-securitypolicy/200702",
+    "http://docs.oasis-open.org/ws-sx/ws-securitypolicy/200802",
+];
+pub const WSU_NS: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd";
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The WS-SecurityPolicy assertions of one alternative of a policy (or of the policies in effect
+/// for an operation)
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PolicyAssertions {
+    /// `sp:TransportBinding`, security provided by the transport (HTTPS)
+    pub transport_binding: bool,
+    /// `sp:SymmetricBinding`/`sp:AsymmetricBinding`, message level security
+    pub message_binding: bool,
+    pub username_token: bool,
+    /// `sp:HashPassword` or `sp:NoPassword` inside the username token
+    pub password_protected: bool,
+    pub include_timestamp: bool,
+    /// `sp:SignedParts`/`sp:SignedElements`
+    pub signed_parts: bool,
+    /// `sp:EncryptedParts`/`sp:EncryptedElements`
+    pub encrypted_parts: bool,
+}
+
+impl PolicyAssertions {
+    pub fn merge(&mut self, other: &PolicyAssertions) {
+        self.transport_binding |= other.transport_binding;
+        self.message_binding |= other.message_binding;
+        self.username_token |= other.username_token;
+        self.password_protected |= other.password_protected;
+        self.include_timestamp |= other.include_timestamp;
+        self.signed_parts |= other.signed_parts;
+        self.encrypted_parts |= other.encrypted_parts;
+    }
+    fn assert(&mut self, name: &str) {
+        match name {
+            "TransportBinding" => self.transport_binding = true,
+            "SymmetricBinding" | "AsymmetricBinding" => self.message_binding = true,
+            "UsernameToken" => self.username_token = true,
+            "HashPassword" | "NoPassword" => self.password_protected = true,
+            "IncludeTimestamp" => self.include_timestamp = true,
+            "SignedParts" | "SignedElements" => self.signed_parts = true,
+            "EncryptedParts" | "EncryptedElements" => self.encrypted_parts = true,
+            _ => {}
+        }
+    }
+    /// Whether any security assertion is made at all
+    pub fn is_secured(&self) -> bool {
+        self.transport_binding || self.message_binding || self.username_token
+    }
+    /// Short human readable form, e.g. `TransportBinding + UsernameToken`
+    pub fn describe(&self) -> String {
+        let names = [
+            (self.transport_binding, "TransportBinding"),
+            (self.message_binding, "MessageBinding"),
+            (self.username_token, "UsernameToken"),
+            (self.include_timestamp, "Timestamp"),
+            (self.signed_parts, "SignedParts"),
+            (self.encrypted_parts, "EncryptedParts"),
+        ];
+        let present: Vec<&str> = names
+            .iter()
+            .filter(|(present, _)| *present)
+            .map(|(_, name)| *name)
+            .collect();
+        if present.is_empty() {
+            "none".to_string()
+        } else {
+            present.join(" + ")
+        }
+    }
+}
+
+/// Every alternative of `left` combined with every alternative of `right`, the alternatives of
+/// both policies applying together
+pub fn combine(left: &[PolicyAssertions], right: &[PolicyAssertions]) -> Vec<PolicyAssertions> {
+    let mut combined: Vec<PolicyAssertions> = Vec::new();
+    for alternative in left {
+        for other in right {
+            let mut merged = alternative.clone();
+            merged.merge(other);
+            // there are only so many distinct alternatives, which bounds nested `ExactlyOne`s
+            if !combined.contains(&merged) {
+                combined.push(merged);
+            }
+        }
+    }
+    combined.sort();
+    combined
+}
+
+/// Whether policy alternatives are secured: a caller can pick any of them, so all have to be
+pub fn is_secured(alternatives: &[PolicyAssertions]) -> bool {
+    alternatives.iter().all(PolicyAssertions::is_secured)
+}
+
+/// Short human readable form of policy alternatives, e.g. `TransportBinding | none`
+pub fn describe(alternatives: &[PolicyAssertions]) -> String {
+    alternatives
+        .iter()
+        .map(PolicyAssertions::describe)
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// A `wsp:Policy`, along with the policies it references
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Policy {
+    pub id: Option<String>,
+    /// the `wsp:ExactlyOne` alternatives of the policy, a single one when it has no choice
+    pub alternatives: Vec<PolicyAssertions>,
+    /// policies referenced anywhere in the policy, they apply to every alternative
+    pub references: Vec<String>,
+    pub line: u64,
+}
+
+/// A policy attached to a WSDL element, inline or by reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PolicyAttachment {
+    Inline(Policy),
+    /// `wsp:PolicyReference URI="#id"` or `wsp:PolicyURIs`, without the leading `#`
+    Reference(String),
+}
+
+pub fn is_policy_element(element: &XmlElement) -> bool {
+    element.name == "Policy" && WS_POLICY_NS.contains(&element.namespace.as_str())
+}
+
+pub fn parse_policy(policy: &XmlElement) -> Policy {
+    let id = policy
+        .attributes
+        .iter()
+        .find(|a| {
+            (a.name == "Id" && a.namespace.as_deref() == Some(WSU_NS))
+                || (a.name == "id" && a.namespace.as_deref() == Some(XML_NS))
+                || (a.namespace.is_none() && (a.name == "Name" || a.name == "Id"))
+        })
+        .map(|a| a.value.clone());
+    let mut references = Vec::new();
+    let alternatives = alternatives_of(policy, &mut references);
+    Policy {
+        id,
+        alternatives,
+        references,
+        line: policy.line,
+    }
+}
+
+/// Alternatives of a policy expression: those of each operand of a `wsp:ExactlyOne`, all
+/// operands combined otherwise (`wsp:All`, `wsp:Policy` and assertions with nested policies)
+fn alternatives_of(element: &XmlElement, references: &mut Vec<String>) -> Vec<PolicyAssertions> {
+    let is_ws_policy = WS_POLICY_NS.contains(&element.namespace.as_str());
+    if is_ws_policy && element.name == "ExactlyOne" {
+        let mut alternatives: Vec<PolicyAssertions> = Vec::new();
+        for child in &element.children {
+            for alternative in alternatives_of(child, references) {
+                if !alternatives.contains(&alternative) {
+                    alternatives.push(alternative);
+                }
+            }
+        }
+        // an `ExactlyOne` without alternatives can't be satisfied, it is left out
+        if alternatives.is_empty() {
+            alternatives.push(PolicyAssertions::default());
+        }
+        alternatives.sort();
+        return alternatives;
+    }
+    if is_ws_policy && element.name == "PolicyReference" {
+        references.extend(
+            element
+                .attr("URI")
+                .map(|uri| uri.trim_start_matches('#').to_string()),
+        );
+    }
+    let mut alternatives = vec![PolicyAssertions::default()];
+    for child in &element.children {
+        alternatives = combine(&alternatives, &alternatives_of(child, references));
+    }
+    if WS_SECURITY_POLICY_NS.contains(&element.namespace.as_str()) {
+        for alternative in &mut alternatives {
+            alternative.assert(&element.name);
+        }
+    }
+    alternatives
+}
+
+/// Policies attached directly to `element`: inline `wsp:Policy` children, `wsp:PolicyReference`s
+/// and the `wsp:PolicyURIs` attribute
+pub fn attached_policies(element: &XmlElement) -> Vec<PolicyAttachment> {
+    let mut attached = Vec::new();
+    for child in &element.children {
+        if is_policy_element(child) {
+            attached.push(PolicyAttachment::Inline(parse_policy(child)));
+        } else if WS_POLICY_NS.contains(&child.namespace.as_str())
+            && child.name == "PolicyReference"
+        {
+            if let Some(uri) = child.attr("URI") {
+                attached.push(PolicyAttachment::Reference(
+                    uri.trim_start_matches('#').to_string(),
+                ));
+            }
+        }
+    }
+    for attribute in &element.attributes {
+        let is_policy_uris = attribute.name == "PolicyURIs"
+            && attribute
+                .namespace
+                .as_deref()
+                .map(|ns| WS_POLICY_NS.contains(&ns))
+                .unwrap_or(false);
+        if is_policy_uris {
+            attached.extend(
+                attribute.value.split_whitespace().map(|uri| {
+                    PolicyAttachment::Reference(uri.trim_start_matches('#').to_string())
+                }),
+            );
+        }
+    }
+    attached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::xml::parse_document;
+
+    fn policy(body: &str) -> Policy {
+        parse_policy(
+            &parse_document(&format!(
+                r#"<wsp:Policy xmlns:wsp="http://www.w3.org/ns/ws-policy" xmlns:sp="http://docs.oasis-open.org/ws-sx/ws-securitypolicy/200702">{}</wsp:Policy>"#,
+                body
+            ))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn an_empty_alternative_makes_security_optional() {
+        let policy = policy(
+            "<wsp:ExactlyOne><wsp:All><sp:TransportBinding/></wsp:All><wsp:All/></wsp:ExactlyOne>",
+        );
+        assert_eq!(policy.alternatives.len(), 2);
+        assert!(!is_secured(&policy.alternatives));
+        assert_eq!(describe(&policy.alternatives), "none | TransportBinding");
+    }
+
+    #[test]
+    fn alternatives_are_combined_with_the_assertions_around_them() {
+        let policy = policy(
+            r#"<sp:IncludeTimestamp/>
+            <wsp:ExactlyOne>
+              <sp:TransportBinding/>
+              <sp:UsernameToken><wsp:Policy><sp:HashPassword/></wsp:Policy></sp:UsernameToken>
+            </wsp:ExactlyOne>"#,
+        );
+        assert!(is_secured(&policy.alternatives));
+        assert!(policy.alternatives.iter().all(|a| a.include_timestamp));
+        let username = policy
+            .alternatives
+            .iter()
+            .find(|a| a.username_token)
+            .unwrap();
+        assert!(username.password_protected && !username.transport_binding);
+    }
+}
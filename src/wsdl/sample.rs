@@ -0,0 +1,522 @@
+use std::fmt::Display;
+
+use crate::wsdl::schema::{ComplexType, ElementDecl, Facets, Schema, SimpleType};
+use crate::wsdl::xml::QName;
+use crate::wsdl::{Binding, BindingOperation, SoapVersion, WsdlDocument, XSD_NS};
+
+pub const SOAP_11_ENVELOPE_NS: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+pub const SOAP_12_ENVELOPE_NS: &str = "http://www.w3.org/2003/05/soap-envelope";
+/// Nesting past which recursive or very deep types are cut short
+const MAX_SAMPLE_DEPTH: usize = 32;
+/// Longest placeholder string written, longer length facets are noted in a comment instead
+const MAX_SAMPLE_LENGTH: u64 = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SampleError {
+    #[error("No operation `{0}` in the WSDL. Operations are: {1}")]
+    UnknownOperation(String, String),
+    #[error("Operation `{0}` has no input message")]
+    NoInput(String),
+}
+
+/// A request to an operation: the envelope with the HTTP headers it has to be sent with
+#[derive(Debug, Clone)]
+pub struct SampleRequest {
+    pub version: SoapVersion,
+    /// address of a port serving the operation
+    pub address: Option<String>,
+    pub soap_action: Option<String>,
+    pub envelope: String,
+}
+
+impl Display for SampleRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "POST {} HTTP/1.1",
+            self.address.as_deref().unwrap_or("/")
+        )?;
+        let action = self.soap_action.as_deref().unwrap_or_default();
+        match self.version {
+            SoapVersion::Soap11 => {
+                writeln!(f, "Content-Type: text/xml; charset=utf-8")?;
+                writeln!(f, "SOAPAction: \"{}\"", action)?;
+            }
+            SoapVersion::Soap12 if action.is_empty() => {
+                writeln!(f, "Content-Type: application/soap+xml; charset=utf-8")?;
+            }
+            SoapVersion::Soap12 => writeln!(
+                f,
+                "Content-Type: application/soap+xml; charset=utf-8; action=\"{}\"",
+                action
+            )?,
+        }
+        writeln!(f)?;
+        write!(f, "{}", self.envelope)
+    }
+}
+
+/// Build a request envelope for `operation` with placeholder values: enumerations' first value,
+/// defaults, and otherwise a value of the XSD type. Optional and repeated elements are marked
+/// with a comment.
+pub fn sample_request(
+    wsdl: &WsdlDocument,
+    operation: &str,
+    version: SoapVersion,
+) -> Result<SampleRequest, SampleError> {
+    let abstract_operation = wsdl
+        .port_types
+        .iter()
+        .flat_map(|port_type| port_type.operations.iter().map(move |o| (port_type, o)))
+        .find(|(_, o)| o.name == operation)
+        .ok_or_else(|| {
+            let names: Vec<&str> = wsdl
+                .port_types
+                .iter()
+                .flat_map(|p| p.operations.iter().map(|o| o.name.as_str()))
+                .collect();
+            SampleError::UnknownOperation(operation.to_string(), names.join(", "))
+        })?;
+    let (port_type, abstract_operation) = abstract_operation;
+    let input = abstract_operation
+        .input
+        .as_ref()
+        .and_then(|input| wsdl.message(input))
+        .ok_or_else(|| SampleError::NoInput(operation.to_string()))?;
+
+    let bound = bound_operation(wsdl, &port_type.name, operation, version);
+    let rpc = bound
+        .and_then(|(binding, o)| o.style.as_ref().or(binding.style.as_ref()))
+        .map(|style| style == "rpc")
+        .unwrap_or(false);
+
+    let mut writer = EnvelopeWriter {
+        wsdl,
+        prefixes: vec![],
+        types: vec![],
+    };
+    let mut body = String::new();
+    if rpc {
+        // the operation wraps its parts, which are unqualified
+        let namespace = bound
+            .and_then(|(_, o)| o.input_namespace.clone())
+            .unwrap_or_else(|| wsdl.target_namespace.clone());
+        let mut parts = Content::default();
+        for part in &input.parts {
+            let content = match &part.type_name {
+                Some(type_name) => writer.typed(type_name, 3),
+                None => Content::text("?"),
+            };
+            parts.children.push_str(&content.write(&part.name, 3));
+        }
+        let tag = writer.tag(&namespace, operation);
+        body.push_str(&parts.write(&tag, 2));
+    } else {
+        for part in &input.parts {
+            if let Some(element) = &part.element {
+                match wsdl.schemas.element(element) {
+                    Some((schema, declaration)) => {
+                        body.push_str(&writer.element_content(declaration, schema, true, 2))
+                    }
+                    None => body.push_str(&format!(
+                        "{}<!--element {} is not declared-->\n",
+                        indent(2),
+                        element.name
+                    )),
+                }
+            } else if let Some(type_name) = &part.type_name {
+                body.push_str(&writer.typed(type_name, 2).write(&part.name, 2));
+            }
+        }
+    }
+
+    let envelope_namespace = match version {
+        SoapVersion::Soap11 => SOAP_11_ENVELOPE_NS,
+        SoapVersion::Soap12 => SOAP_12_ENVELOPE_NS,
+    };
+    let mut envelope = format!("<soapenv:Envelope xmlns:soapenv=\"{}\"", envelope_namespace);
+    for (namespace, prefix) in &writer.prefixes {
+        envelope.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape(namespace)));
+    }
+    envelope.push_str(">\n");
+    envelope.push_str(&format!("{}<soapenv:Header/>\n", indent(1)));
+    envelope.push_str(&format!("{}<soapenv:Body>\n", indent(1)));
+    envelope.push_str(&body);
+    envelope.push_str(&format!("{}</soapenv:Body>\n", indent(1)));
+    envelope.push_str("</soapenv:Envelope>\n");
+
+    let address = bound.and_then(|(binding, _)| {
+        wsdl.services
+            .iter()
+            .flat_map(|service| service.ports.iter())
+            .find(|port| port.binding.as_ref().map(|b| &b.name) == Some(&binding.name))
+            .and_then(|port| port.address.clone())
+    });
+    Ok(SampleRequest {
+        version,
+        address,
+        soap_action: bound.and_then(|(_, o)| o.soap_action.clone()),
+        envelope,
+    })
+}
+
+/// The binding operation implementing `operation`, from a binding of `version` if there is one
+fn bound_operation<'a>(
+    wsdl: &'a WsdlDocument,
+    port_type: &str,
+    operation: &str,
+    version: SoapVersion,
+) -> Option<(&'a Binding, &'a BindingOperation)> {
+    let mut bindings: Vec<&Binding> = wsdl
+        .bindings
+        .iter()
+        .filter(|b| b.port_type.as_ref().map(|p| p.name.as_str()) == Some(port_type))
+        .collect();
+    bindings.sort_by_key(|b| match b.soap_version {
+        Some(v) if v == version => 0,
+        Some(_) => 1,
+        None => 2,
+    });
+    bindings.into_iter().find_map(|binding| {
+        binding
+            .operations
+            .iter()
+            .find(|o| o.name == operation)
+            .map(|o| (binding, o))
+    })
+}
+
+/// What goes inside an element: attributes, then either child elements or a text value
+#[derive(Debug, Default)]
+struct Content {
+    attributes: Vec<(String, String)>,
+    text: Option<String>,
+    /// child elements, already written out
+    children: String,
+    /// comments written before the element, about values that don't meet the schema
+    notes: Vec<String>,
+}
+
+impl Content {
+    fn text(value: &str) -> Self {
+        Self {
+            text: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+    fn placeholder((value, note): (String, Option<String>)) -> Self {
+        Self {
+            text: Some(value),
+            notes: note.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+    fn write(&self, tag: &str, depth: usize) -> String {
+        let mut start: String = self
+            .notes
+            .iter()
+            .map(|note| format!("{}<!--{}-->\n", indent(depth), note))
+            .collect();
+        start.push_str(&format!("{}<{}", indent(depth), tag));
+        for (name, value) in &self.attributes {
+            start.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+        }
+        if !self.children.is_empty() {
+            format!("{}>\n{}{}</{}>\n", start, self.children, indent(depth), tag)
+        } else if let Some(text) = &self.text {
+            format!("{}>{}</{}>\n", start, escape(text), tag)
+        } else {
+            format!("{}/>\n", start)
+        }
+    }
+}
+
+struct EnvelopeWriter<'w> {
+    wsdl: &'w WsdlDocument,
+    /// namespaces used so far with their prefix, declared on the envelope
+    prefixes: Vec<(String, String)>,
+    /// named types being written, to cut recursion
+    types: Vec<QName>,
+}
+
+impl EnvelopeWriter<'_> {
+    /// `name` qualified with a prefix bound to `namespace`, as is when it has no namespace
+    fn tag(&mut self, namespace: &str, name: &str) -> String {
+        if namespace.is_empty() {
+            return name.to_string();
+        }
+        let prefix = match self.prefixes.iter().find(|(ns, _)| ns == namespace) {
+            Some((_, prefix)) => prefix.clone(),
+            None => {
+                let prefix = format!("ns{}", self.prefixes.len() + 1);
+                self.prefixes.push((namespace.to_string(), prefix.clone()));
+                prefix
+            }
+        };
+        format!("{}:{}", prefix, name)
+    }
+
+    /// A particle of a complex type, preceded by a comment when it's optional or repeated
+    fn element(&mut self, element: &ElementDecl, schema: &Schema, depth: usize) -> String {
+        let occurrence = match (element.min_occurs, element.max_occurs) {
+            (0, Some(1)) => Some("Optional:".to_string()),
+            (0, _) => Some("Zero or more repetitions:".to_string()),
+            (min, None) => Some(format!("{} or more repetitions:", min)),
+            (min, Some(max)) if max > 1 => Some(format!("{} to {} repetitions:", min, max)),
+            _ => None,
+        };
+        let mut written = occurrence
+            .map(|comment| format!("{}<!--{}-->\n", indent(depth), comment))
+            .unwrap_or_default();
+        match &element.reference {
+            Some(reference) => match self.wsdl.schemas.element(reference) {
+                Some((schema, global)) => {
+                    written.push_str(&self.element_content(global, schema, true, depth))
+                }
+                None => written.push_str(&format!(
+                    "{}<!--element {} is not declared-->\n",
+                    indent(depth),
+                    reference.name
+                )),
+            },
+            None => written.push_str(&self.element_content(element, schema, false, depth)),
+        }
+        written
+    }
+
+    /// The element itself. Global elements, and local ones of `elementFormDefault="qualified"`
+    /// schemas, are in the target namespace.
+    fn element_content(
+        &mut self,
+        element: &ElementDecl,
+        schema: &Schema,
+        global: bool,
+        depth: usize,
+    ) -> String {
+        let namespace = if global || schema.qualified {
+            schema.target_namespace.as_str()
+        } else {
+            ""
+        };
+        let tag = self.tag(namespace, &element.name);
+        let mut content = if let Some(complex_type) = &element.complex_type {
+            self.complex(complex_type, schema, depth)
+        } else if let Some(simple_type) = &element.simple_type {
+            Content::placeholder(self.simple_value(simple_type))
+        } else if let Some(type_name) = &element.type_name {
+            self.typed(type_name, depth)
+        } else {
+            Content::text("?")
+        };
+        if let (Some(default), true) = (&element.default, content.children.is_empty()) {
+            content.text = Some(default.clone());
+        }
+        content.write(&tag, depth)
+    }
+
+    /// Content of an element or attribute of the named type `type_name`
+    fn typed(&mut self, type_name: &QName, depth: usize) -> Content {
+        if type_name.namespace == XSD_NS {
+            return Content::placeholder(placeholder(&type_name.name, &Facets::default()));
+        }
+        let wsdl = self.wsdl;
+        if let Some(simple_type) = wsdl.schemas.simple_type(type_name) {
+            return Content::placeholder(self.simple_value(simple_type));
+        }
+        let Some((schema, complex_type)) = wsdl.schemas.complex_type(type_name) else {
+            return Content::text("?");
+        };
+        if self.types.contains(type_name) || depth > MAX_SAMPLE_DEPTH {
+            return Content {
+                children: format!("{}<!--{} nests itself-->\n", indent(depth + 1), type_name),
+                ..Default::default()
+            };
+        }
+        self.types.push(type_name.clone());
+        let content = self.complex(complex_type, schema, depth);
+        self.types.pop();
+        content
+    }
+
+    fn complex(&mut self, complex_type: &ComplexType, schema: &Schema, depth: usize) -> Content {
+        // an extension starts with the content of its base
+        let mut content = match &complex_type.base {
+            Some(base) => self.typed(base, depth),
+            None => Content::default(),
+        };
+        for element in &complex_type.elements {
+            let written = self.element(element, schema, depth + 1);
+            content.children.push_str(&written);
+        }
+        if !complex_type.any_elements.is_empty() {
+            content.children.push_str(&format!(
+                "{}<!--You may enter ANY elements at this point-->\n",
+                indent(depth + 1)
+            ));
+        }
+        for attribute in &complex_type.attributes {
+            let value = match (
+                &attribute.default,
+                &attribute.simple_type,
+                &attribute.type_name,
+            ) {
+                (Some(default), _, _) => default.clone(),
+                (None, Some(simple_type), _) => {
+                    let (value, note) = self.simple_value(simple_type);
+                    content
+                        .notes
+                        .extend(note.map(|note| attribute_note(&attribute.name, &note)));
+                    value
+                }
+                (None, None, Some(type_name)) => {
+                    let typed = self.typed(type_name, depth);
+                    let notes = typed.notes.iter();
+                    content
+                        .notes
+                        .extend(notes.map(|note| attribute_note(&attribute.name, note)));
+                    typed.text.unwrap_or_default()
+                }
+                (None, None, None) => "?".to_string(),
+            };
+            content.attributes.push((attribute.name.clone(), value));
+        }
+        content
+    }
+
+    /// A value of `simple_type`, with a note when it had to be shortened
+    fn simple_value(&self, simple_type: &SimpleType) -> (String, Option<String>) {
+        let (base, facets) = self.wsdl.schemas.restriction_of(simple_type);
+        match facets.enumeration.first() {
+            Some(first) => (first.clone(), None),
+            None => placeholder(
+                base.as_ref().map(|b| b.name.as_str()).unwrap_or("string"),
+                &facets,
+            ),
+        }
+    }
+}
+
+/// A valid value of the built in XSD type `name` within `facets`. Strings are at most
+/// `MAX_SAMPLE_LENGTH` long, the note gives the length the facets require when they ask for more.
+fn placeholder(name: &str, facets: &Facets) -> (String, Option<String>) {
+    if let Some(minimum) = &facets.min_inclusive {
+        return (minimum.clone(), None);
+    }
+    let value = match name {
+        "boolean" => "false".to_string(),
+        "int" | "integer" | "long" | "short" | "byte" | "nonNegativeInteger" | "unsignedLong"
+        | "unsignedInt" | "unsignedShort" | "unsignedByte" => "0".to_string(),
+        "positiveInteger" => "1".to_string(),
+        "negativeInteger" | "nonPositiveInteger" => "-1".to_string(),
+        "decimal" | "double" | "float" => "0.0".to_string(),
+        "date" => "2000-01-01".to_string(),
+        "dateTime" => "2000-01-01T00:00:00".to_string(),
+        "time" => "00:00:00".to_string(),
+        "duration" => "P1D".to_string(),
+        "gYear" => "2000".to_string(),
+        "base64Binary" => "AA==".to_string(),
+        "hexBinary" => "00".to_string(),
+        "anyURI" => "http://example.com".to_string(),
+        "string" | "normalizedString" | "token" | "Name" | "NCName" | "language" | "ID" => {
+            let (exact, length) = match facets.length.or(facets.max_length.filter(|max| *max < 6)) {
+                Some(length) => (true, length),
+                None => (false, facets.min_length.unwrap_or(0).max(6)),
+            };
+            let written = length.min(MAX_SAMPLE_LENGTH) as usize;
+            let value = if exact {
+                "s".repeat(written)
+            } else {
+                format!("string{}", "s".repeat(written - 6))
+            };
+            let note = (length > MAX_SAMPLE_LENGTH).then(|| {
+                format!(
+                    "Shortened to {} characters, the schema requires {}",
+                    written, length
+                )
+            });
+            return (value, note);
+        }
+        _ => "?".to_string(),
+    };
+    (value, None)
+}
+
+fn attribute_note(attribute: &str, note: &str) -> String {
+    format!("Attribute {}: {}", attribute, note)
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wsdl_with_facet(facet: &str) -> WsdlDocument {
+        let text = format!(
+            r#"<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:t" targetNamespace="urn:t">
+  <types>
+    <xs:schema targetNamespace="urn:t" elementFormDefault="qualified">
+      <xs:simpleType name="Code"><xs:restriction base="xs:string">{}</xs:restriction></xs:simpleType>
+      <xs:element name="Lookup"><xs:complexType><xs:sequence><xs:element name="code" type="tns:Code"/></xs:sequence><xs:attribute name="tag" type="tns:Code"/></xs:complexType></xs:element>
+    </xs:schema>
+  </types>
+  <message name="LookupRequest"><part name="parameters" element="tns:Lookup"/></message>
+  <portType name="Port"><operation name="Lookup"><input message="tns:LookupRequest"/></operation></portType>
+  <binding name="Binding" type="tns:Port">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <operation name="Lookup"><soap:operation soapAction="urn:t:Lookup"/></operation>
+  </binding>
+</definitions>"#,
+            facet
+        );
+        WsdlDocument::parse(&text).unwrap()
+    }
+
+    #[test]
+    fn huge_length_facets_are_capped_and_noted() {
+        for facet in [
+            r#"<xs:length value="18446744073709551615"/>"#,
+            r#"<xs:length value="3000000000"/>"#,
+            r#"<xs:minLength value="3000000000"/>"#,
+        ] {
+            let wsdl = wsdl_with_facet(facet);
+            let request = sample_request(&wsdl, "Lookup", SoapVersion::Soap11).unwrap();
+            let value = "s".repeat(MAX_SAMPLE_LENGTH as usize - 6);
+            assert!(request.envelope.contains(&value), "{}", request.envelope);
+            assert!(!request
+                .envelope
+                .contains(&format!("{}s", "s".repeat(MAX_SAMPLE_LENGTH as usize))));
+            assert!(
+                request
+                    .envelope
+                    .contains("<!--Shortened to 64 characters, the schema requires"),
+                "{}",
+                request.envelope
+            );
+            assert!(request
+                .envelope
+                .contains("<!--Attribute tag: Shortened to 64 characters"));
+        }
+    }
+
+    #[test]
+    fn short_length_facets_are_met() {
+        let wsdl = wsdl_with_facet(r#"<xs:length value="3"/>"#);
+        let request = sample_request(&wsdl, "Lookup", SoapVersion::Soap11).unwrap();
+        assert!(
+            request.envelope.contains(">sss</ns1:code>"),
+            "{}",
+            request.envelope
+        );
+        assert!(!request.envelope.contains("Shortened"));
+    }
+}
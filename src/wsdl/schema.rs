@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+
+use crate::wsdl::xml::{QName, XmlElement};
+use crate::wsdl::XSD_NS;
+
+/// Built in XSD types holding free text
+pub const XSD_STRING_TYPES: [&str; 7] = [
+    "string",
+    "normalizedString",
+    "token",
+    "anyURI",
+    "Name",
+    "NCName",
+    "language",
+];
+
+/// Restricting facets of a simple type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Facets {
+    pub length: Option<u64>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+    pub enumeration: Vec<String>,
+    pub min_inclusive: Option<String>,
+    pub max_inclusive: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleType {
+    pub name: Option<String>,
+    /// base of the `restriction`, `None` for lists and unions
+    pub base: Option<QName>,
+    pub facets: Facets,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeDecl {
+    pub name: String,
+    pub type_name: Option<QName>,
+    pub simple_type: Option<SimpleType>,
+    pub required: bool,
+    pub default: Option<String>,
+    pub line: u64,
+}
+
+/// An `element` declaration, either global or a particle of a complex type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementDecl {
+    /// declared name, or the local name of the referenced element for `ref`s
+    pub name: String,
+    pub type_name: Option<QName>,
+    /// the global element referenced through `ref="..."`
+    pub reference: Option<QName>,
+    pub min_occurs: u64,
+    /// `None` when `maxOccurs="unbounded"`
+    pub max_occurs: Option<u64>,
+    pub default: Option<String>,
+    pub nillable: bool,
+    /// anonymous complex type declared inline
+    pub complex_type: Option<Box<ComplexType>>,
+    /// anonymous simple type declared inline
+    pub simple_type: Option<SimpleType>,
+    pub line: u64,
+}
+
+/// An `any` or `anyAttribute` wildcard, accepting elements or attributes not declared by the schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wildcard {
+    /// `namespace` constraint, `##any` by default
+    pub namespace: String,
+    /// `strict` (the default), `lax` or `skip`
+    pub process_contents: String,
+    pub line: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexType {
+    pub name: Option<String>,
+    pub mixed: bool,
+    /// base type of a `complexContent`/`simpleContent` extension or restriction
+    pub base: Option<QName>,
+    /// element particles of every `sequence`, `all` and `choice`, flattened
+    pub elements: Vec<ElementDecl>,
+    pub attributes: Vec<AttributeDecl>,
+    /// `any` element wildcards
+    pub any_elements: Vec<Wildcard>,
+    pub any_attribute: Option<Wildcard>,
+    pub line: u64,
+}
+
+/// One `xs:schema`, typically embedded in the `types` section of a WSDL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schema {
+    pub target_namespace: String,
+    /// `elementFormDefault="qualified"`, local elements are namespace qualified
+    pub qualified: bool,
+    pub elements: Vec<ElementDecl>,
+    pub complex_types: Vec<ComplexType>,
+    pub simple_types: Vec<SimpleType>,
+    /// file the schema was imported from, `None` when embedded in the scanned document
+    pub location: Option<String>,
+    pub line: u64,
+}
+
+impl Schema {
+    pub fn parse(schema: &XmlElement) -> Self {
+        let mut parsed = Self {
+            target_namespace: schema
+                .attr("targetNamespace")
+                .unwrap_or_default()
+                .to_string(),
+            qualified: schema.attr("elementFormDefault") == Some("qualified"),
+            elements: vec![],
+            complex_types: vec![],
+            simple_types: vec![],
+            location: None,
+            line: schema.line,
+        };
+        for child in schema.children.iter().filter(|c| c.namespace == XSD_NS) {
+            match child.name.as_str() {
+                "element" => parsed.elements.push(parse_element(child)),
+                "complexType" => parsed.complex_types.push(parse_complex_type(child)),
+                "simpleType" => parsed.simple_types.push(parse_simple_type(child)),
+                _ => {}
+            }
+        }
+        parsed
+    }
+}
+
+/// The schemas of a WSDL, with lookups of global declarations by qualified name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaSet {
+    pub schemas: Vec<Schema>,
+}
+
+impl SchemaSet {
+    /// The global element `name`, along with the schema declaring it
+    pub fn element(&self, name: &QName) -> Option<(&Schema, &ElementDecl)> {
+        self.in_namespace(&name.namespace).find_map(|schema| {
+            schema
+                .elements
+                .iter()
+                .find(|e| e.name == name.name)
+                .map(|e| (schema, e))
+        })
+    }
+    /// The named complex type `name`, along with the schema declaring it
+    pub fn complex_type(&self, name: &QName) -> Option<(&Schema, &ComplexType)> {
+        self.in_namespace(&name.namespace).find_map(|schema| {
+            schema
+                .complex_types
+                .iter()
+                .find(|t| t.name.as_deref() == Some(name.name.as_str()))
+                .map(|t| (schema, t))
+        })
+    }
+    pub fn simple_type(&self, name: &QName) -> Option<&SimpleType> {
+        self.in_namespace(&name.namespace)
+            .flat_map(|schema| schema.simple_types.iter())
+            .find(|t| t.name.as_deref() == Some(name.name.as_str()))
+    }
+    /// Follow a simple type's restriction chain down to the built in XSD type it restricts,
+    /// merging the facets met on the way (the most derived facet wins). A circular chain
+    /// restricts no built in type, its base is `None`.
+    pub fn restriction_of(&self, simple_type: &SimpleType) -> (Option<QName>, Facets) {
+        let mut facets = simple_type.facets.clone();
+        let mut base = simple_type.base.clone();
+        let mut visited: Vec<QName> = Vec::new();
+        while let Some(name) = base.take() {
+            let Some(derived) = self.simple_type(&name) else {
+                base = Some(name);
+                break;
+            };
+            if visited.contains(&name) {
+                break;
+            }
+            visited.push(name);
+            let inherited = &derived.facets;
+            facets.length = facets.length.or(inherited.length);
+            facets.min_length = facets.min_length.or(inherited.min_length);
+            facets.max_length = facets.max_length.or(inherited.max_length);
+            facets.pattern = facets.pattern.or(inherited.pattern.clone());
+            if facets.enumeration.is_empty() {
+                facets.enumeration = inherited.enumeration.clone();
+            }
+            facets.min_inclusive = facets.min_inclusive.or(inherited.min_inclusive.clone());
+            facets.max_inclusive = facets.max_inclusive.or(inherited.max_inclusive.clone());
+            base = derived.base.clone();
+        }
+        (base, facets)
+    }
+    fn in_namespace<'s: 'n, 'n>(
+        &'s self,
+        namespace: &'n str,
+    ) -> impl Iterator<Item = &'s Schema> + 'n {
+        self.schemas
+            .iter()
+            .filter(move |schema| schema.target_namespace == namespace)
+    }
+}
+
+/// Whether `name` is one of the built in XSD string types
+pub fn is_xsd_string(name: &QName) -> bool {
+    name.namespace == XSD_NS && XSD_STRING_TYPES.contains(&name.name.as_str())
+}
+
+fn parse_element(element: &XmlElement) -> ElementDecl {
+    let reference = element.qname_attr("ref");
+    let name = element
+        .attr("name")
+        .map(String::from)
+        .or_else(|| reference.as_ref().map(|r| r.name.clone()))
+        .unwrap_or_default();
+    let occurs = |attribute: &str, default: u64| -> Option<u64> {
+        match element.attr(attribute) {
+            Some("unbounded") => None,
+            Some(value) => Some(value.trim().parse().unwrap_or(default)),
+            None => Some(default),
+        }
+    };
+    ElementDecl {
+        name,
+        type_name: element.qname_attr("type"),
+        reference,
+        min_occurs: occurs("minOccurs", 1).unwrap_or(1),
+        max_occurs: occurs("maxOccurs", 1),
+        default: element.attr("default").map(String::from),
+        nillable: element.attr("nillable") == Some("true"),
+        complex_type: element
+            .child(XSD_NS, "complexType")
+            .map(|t| Box::new(parse_complex_type(t))),
+        simple_type: element.child(XSD_NS, "simpleType").map(parse_simple_type),
+        line: element.line,
+    }
+}
+
+fn parse_complex_type(complex_type: &XmlElement) -> ComplexType {
+    let mut parsed = ComplexType {
+        name: complex_type.attr("name").map(String::from),
+        mixed: complex_type.attr("mixed") == Some("true"),
+        base: None,
+        elements: vec![],
+        attributes: vec![],
+        any_elements: vec![],
+        any_attribute: None,
+        line: complex_type.line,
+    };
+    collect_content(complex_type, &mut parsed);
+    parsed
+}
+
+/// Gather the particles and attributes of a content model, through compositors and derivations
+fn collect_content(model: &XmlElement, complex_type: &mut ComplexType) {
+    for child in model.children.iter().filter(|c| c.namespace == XSD_NS) {
+        match child.name.as_str() {
+            "element" => complex_type.elements.push(parse_element(child)),
+            "attribute" => complex_type.attributes.push(parse_attribute(child)),
+            "any" => complex_type.any_elements.push(parse_wildcard(child)),
+            "anyAttribute" => complex_type.any_attribute = Some(parse_wildcard(child)),
+            "sequence" | "all" | "choice" | "group" => collect_content(child, complex_type),
+            "complexContent" | "simpleContent" => {
+                if child.attr("mixed") == Some("true") {
+                    complex_type.mixed = true;
+                }
+                collect_content(child, complex_type)
+            }
+            "extension" | "restriction" => {
+                complex_type.base = child.qname_attr("base");
+                collect_content(child, complex_type)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_wildcard(wildcard: &XmlElement) -> Wildcard {
+    Wildcard {
+        namespace: wildcard.attr("namespace").unwrap_or("##any").to_string(),
+        process_contents: wildcard
+            .attr("processContents")
+            .unwrap_or("strict")
+            .to_string(),
+        line: wildcard.line,
+    }
+}
+
+fn parse_attribute(attribute: &XmlElement) -> AttributeDecl {
+    AttributeDecl {
+        name: attribute
+            .attr("name")
+            .map(String::from)
+            .or_else(|| attribute.qname_attr("ref").map(|r| r.name))
+            .unwrap_or_default(),
+        type_name: attribute.qname_attr("type"),
+        simple_type: attribute.child(XSD_NS, "simpleType").map(parse_simple_type),
+        required: attribute.attr("use") == Some("required"),
+        default: attribute.attr("default").map(String::from),
+        line: attribute.line,
+    }
+}
+
+fn parse_simple_type(simple_type: &XmlElement) -> SimpleType {
+    let mut parsed = SimpleType {
+        name: simple_type.attr("name").map(String::from),
+        base: None,
+        facets: Facets::default(),
+        line: simple_type.line,
+    };
+    if let Some(restriction) = simple_type.child(XSD_NS, "restriction") {
+        parsed.base = restriction.qname_attr("base");
+        for facet in restriction
+            .children
+            .iter()
+            .filter(|c| c.namespace == XSD_NS)
+        {
+            let value = facet.attr("value").unwrap_or_default();
+            let number = value.trim().parse::<u64>().ok();
+            let facets = &mut parsed.facets;
+            match facet.name.as_str() {
+                "length" => facets.length = number,
+                "minLength" => facets.min_length = number,
+                "maxLength" => facets.max_length = number,
+                "pattern" => facets.pattern = Some(value.to_string()),
+                "enumeration" => facets.enumeration.push(value.to_string()),
+                "minInclusive" => facets.min_inclusive = Some(value.to_string()),
+                "maxInclusive" => facets.max_inclusive = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsdl::xml::parse_document;
+
+    fn schemas(types: &str) -> SchemaSet {
+        let schema = parse_document(&format!(
+            r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:t" targetNamespace="urn:t">{}</xs:schema>"#,
+            types
+        ))
+        .unwrap();
+        SchemaSet {
+            schemas: vec![Schema::parse(&schema)],
+        }
+    }
+
+    #[test]
+    fn restriction_chains_end_at_the_built_in_type() {
+        let schemas = schemas(
+            r#"<xs:simpleType name="Code"><xs:restriction base="xs:string"><xs:maxLength value="8"/></xs:restriction></xs:simpleType>
+            <xs:simpleType name="CountryCode"><xs:restriction base="tns:Code"><xs:length value="2"/></xs:restriction></xs:simpleType>"#,
+        );
+        let (base, facets) = schemas.restriction_of(&schemas.schemas[0].simple_types[1]);
+        assert_eq!(base, Some(QName::new(XSD_NS, "string")));
+        assert_eq!((facets.length, facets.max_length), (Some(2), Some(8)));
+    }
+
+    #[test]
+    fn circular_restriction_chains_end_without_a_base() {
+        let schemas = schemas(
+            r#"<xs:simpleType name="A"><xs:restriction base="tns:B"><xs:maxLength value="8"/></xs:restriction></xs:simpleType>
+            <xs:simpleType name="B"><xs:restriction base="tns:A"><xs:pattern value="[a-z]+"/></xs:restriction></xs:simpleType>"#,
+        );
+        let (base, facets) = schemas.restriction_of(&schemas.schemas[0].simple_types[0]);
+        assert_eq!(base, None);
+        assert_eq!(facets.max_length, Some(8));
+        assert_eq!(facets.pattern.as_deref(), Some("[a-z]+"));
+    }
+}
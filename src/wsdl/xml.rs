@@ -0,0 +1,327 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use xml::common::Position;
+use xml::reader::{EventReader, ParserConfig2, XmlEvent};
+
+use crate::parser::ParserError;
+use crate::wsdl::dtd::find_doctype;
+
+/// Largest document accepted, in bytes
+pub const MAX_DOCUMENT_SIZE: usize = 32 * 1024 * 1024;
+/// Deepest element nesting accepted
+pub const MAX_DEPTH: usize = 256;
+/// Most attributes (namespace declarations included) accepted on one element
+pub const MAX_ATTRIBUTES: usize = 256;
+
+/// An attribute of an [`XmlElement`], `namespace` is only set for prefixed attributes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmlAttribute {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub value: String,
+}
+
+/// A namespace aware XML element along with its whole subtree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct XmlElement {
+    /// namespace URI the element name resolves to, empty when it has none
+    pub namespace: String,
+    /// local name, without prefix
+    pub name: String,
+    pub attributes: Vec<XmlAttribute>,
+    /// prefixes in scope mapped to their namespace URI, `""` being the default namespace
+    pub namespaces: BTreeMap<String, String>,
+    pub children: Vec<XmlElement>,
+    /// concatenated text and CDATA content
+    pub text: String,
+    /// 1 based line the start tag is on
+    pub line: u64,
+}
+
+/// An XML qualified name resolved against the namespaces in scope, e.g. `tns:Lines`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct QName {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl QName {
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for QName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl XmlElement {
+    /// Whether the element is `name` in namespace `namespace`
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+    /// Value of the unprefixed attribute `name`
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.namespace.is_none() && a.name == name)
+            .map(|a| a.value.as_str())
+    }
+    /// Value of the attribute `name` in namespace `namespace`, e.g. `wsoap:action`
+    pub fn attr_ns(&self, namespace: &str, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.namespace.as_deref() == Some(namespace) && a.name == name)
+            .map(|a| a.value.as_str())
+    }
+    /// Direct children named `name` in namespace `namespace`
+    pub fn children_named<'a>(
+        &'a self,
+        namespace: &'a str,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |c| c.is(namespace, name))
+    }
+    /// First direct child named `name` in namespace `namespace`
+    pub fn child(&self, namespace: &str, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.is(namespace, name))
+    }
+    /// Every element of the subtree below this one, depth first
+    pub fn descendants(&self) -> Vec<&XmlElement> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<&XmlElement> = self.children.iter().rev().collect();
+        while let Some(element) = stack.pop() {
+            descendants.push(element);
+            stack.extend(element.children.iter().rev());
+        }
+        descendants
+    }
+    /// Resolve a QName valued attribute (`type="tns:Lines"`) with the namespaces in scope.
+    /// <br>Unprefixed names resolve to the default namespace, as XSD does.
+    pub fn resolve_qname(&self, value: &str) -> QName {
+        let (prefix, name) = match value.trim().split_once(':') {
+            Some((prefix, name)) => (prefix, name),
+            None => ("", value.trim()),
+        };
+        let namespace = self.namespaces.get(prefix).cloned().unwrap_or_default();
+        QName::new(namespace, name)
+    }
+    /// Resolve the QName valued attribute `name`, if present
+    pub fn qname_attr(&self, name: &str) -> Option<QName> {
+        self.attr(name).map(|value| self.resolve_qname(value))
+    }
+}
+
+/// Qualified name of the root element, read without parsing the rest of the document.
+/// <br>`None` when the text isn't XML.
+pub fn root_name(text: &str) -> Option<QName> {
+    let reader = EventReader::new(text.as_bytes());
+    for event in reader {
+        match event {
+            Ok(XmlEvent::StartElement { name, .. }) => {
+                return Some(QName::new(
+                    name.namespace.unwrap_or_default(),
+                    name.local_name,
+                ))
+            }
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// Read the document at `path`, refusing anything but a regular file of at most
+/// [`MAX_DOCUMENT_SIZE`] bytes. The size is checked while reading: devices, FIFOs and files
+/// growing under our feet don't report a meaningful length up front.
+pub fn read_document(path: &Path) -> io::Result<String> {
+    if !fs::metadata(path)?.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a regular file",
+        ));
+    }
+    let mut text = String::new();
+    File::open(path)?
+        .take(MAX_DOCUMENT_SIZE as u64 + 1)
+        .read_to_string(&mut text)?;
+    if text.len() > MAX_DOCUMENT_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("more than the {} bytes accepted", MAX_DOCUMENT_SIZE),
+        ));
+    }
+    Ok(text)
+}
+
+/// Parse an untrusted XML document into its root element.
+/// <br>The DTD is never processed: the DOCTYPE is blanked out before parsing, so entities it
+/// declares are undefined and referencing one fails the parse. Size, nesting depth and attribute
+/// count are bounded by [`MAX_DOCUMENT_SIZE`], [`MAX_DEPTH`] and [`MAX_ATTRIBUTES`].
+pub fn parse_document(text: &str) -> Result<XmlElement, ParserError> {
+    if text.len() > MAX_DOCUMENT_SIZE {
+        return Err(ParserError::InvalidSOAPSpec(format!(
+            "the document is {} bytes long, more than the {} bytes accepted",
+            text.len(),
+            MAX_DOCUMENT_SIZE
+        )));
+    }
+    let text = without_doctype(text);
+    let config = ParserConfig2::new()
+        .allow_multiple_root_elements(false)
+        .max_attributes(MAX_ATTRIBUTES)
+        // only entities declared by a DTD get expanded, and there is none left
+        .max_entity_expansion_depth(0);
+    let mut reader = EventReader::new_with_config(text.as_bytes(), config);
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+
+    loop {
+        let event = reader.next().map_err(|e| {
+            ParserError::InvalidSOAPSpec(format!("line {}: {}", e.position().row + 1, e.msg()))
+        })?;
+        match event {
+            XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace,
+            } => {
+                let line = reader.position().row + 1;
+                if stack.len() >= MAX_DEPTH {
+                    return Err(ParserError::InvalidSOAPSpec(format!(
+                        "line {}: elements are nested more than {} levels deep",
+                        line, MAX_DEPTH
+                    )));
+                }
+                stack.push(XmlElement {
+                    namespace: name.namespace.unwrap_or_default(),
+                    name: name.local_name,
+                    attributes: attributes
+                        .into_iter()
+                        .map(|a| XmlAttribute {
+                            namespace: a.name.namespace,
+                            name: a.name.local_name,
+                            value: a.value,
+                        })
+                        .collect(),
+                    namespaces: namespace
+                        .0
+                        .into_iter()
+                        .filter(|(prefix, _)| prefix != "xml" && prefix != "xmlns")
+                        .collect(),
+                    children: vec![],
+                    text: String::new(),
+                    line,
+                });
+            }
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or_else(|| {
+                    ParserError::InvalidSOAPSpec("unbalanced end tag".to_string())
+                })?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text);
+                }
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+
+    root.ok_or_else(|| ParserError::InvalidSOAPSpec("the document has no root element".to_string()))
+}
+
+/// `text` with its DOCTYPE declaration replaced by spaces, line breaks kept so that lines
+/// don't move
+fn without_doctype(text: &str) -> Cow<'_, str> {
+    let Some(doctype) = find_doctype(text) else {
+        return Cow::Borrowed(text);
+    };
+    let (start, end) = doctype.span;
+    let blank: String = text[start..end]
+        .chars()
+        .map(|c| if c == '\n' { c } else { ' ' })
+        .collect();
+    Cow::Owned(format!("{}{}{}", &text[..start], blank, &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_document_refuses_what_is_not_a_regular_file() {
+        let error = read_document(Path::new("/dev/zero")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(read_document(&std::env::temp_dir()).is_err());
+    }
+
+    #[test]
+    fn read_document_refuses_oversized_files() {
+        let path =
+            std::env::temp_dir().join(format!("rustysec-oversized-{}.xml", std::process::id()));
+        fs::write(&path, vec![b' '; MAX_DOCUMENT_SIZE + 1]).unwrap();
+        let result = read_document(&path);
+        fs::write(&path, vec![b' '; MAX_DOCUMENT_SIZE]).unwrap();
+        let at_limit = read_document(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(at_limit.unwrap().len(), MAX_DOCUMENT_SIZE);
+    }
+
+    fn nested(depth: usize) -> String {
+        format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth))
+    }
+
+    fn with_attributes(count: usize) -> String {
+        let attributes: String = (0..count).map(|i| format!(" a{}=\"\"", i)).collect();
+        format!("<root{}/>", attributes)
+    }
+
+    #[test]
+    fn parse_document_bounds_nesting_depth() {
+        assert!(parse_document(&nested(MAX_DEPTH)).is_ok());
+        let error = parse_document(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("nested more than 256 levels deep"));
+    }
+
+    #[test]
+    fn parse_document_bounds_attribute_count() {
+        assert_eq!(
+            parse_document(&with_attributes(MAX_ATTRIBUTES))
+                .unwrap()
+                .attributes
+                .len(),
+            MAX_ATTRIBUTES
+        );
+        assert!(parse_document(&with_attributes(MAX_ATTRIBUTES + 1)).is_err());
+    }
+
+    #[test]
+    fn parse_document_bounds_document_size() {
+        let padding = " ".repeat(MAX_DOCUMENT_SIZE);
+        let error = parse_document(&format!("<root/>{}", padding)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("more than the 33554432 bytes accepted"));
+        assert!(parse_document(&format!("<root/>{}", &padding[7..])).is_ok());
+    }
+}